@@ -0,0 +1,472 @@
+use crate::conversion::{ConversionError, Convertible, Rounding, Side};
+use crate::dolar::{Cotizacion, Moneda};
+use crate::parser::{CompraVenta, PrecioCompraVenta};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// An enum describing all possible errors while computing a metric
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnalyticsError {
+    /// There's no quote for the given currency type
+    MissingCotizacion(Cotizacion),
+    /// The quote only has a compra price (Ex: CotizacionValor)
+    MissingVenta(Cotizacion),
+    /// The quotes are priced in different currencies
+    MonedaMismatch(Moneda, Moneda),
+    /// An arithmetic operation failed
+    Conversion(ConversionError),
+}
+
+impl fmt::Display for AnalyticsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCotizacion(cotizacion) => write!(f, "missing quote for {}", cotizacion),
+            Self::MissingVenta(cotizacion) => write!(f, "quote for {} only has compra", cotizacion),
+            Self::MonedaMismatch(a, b) => write!(f, "quotes are priced in {} and {}", a, b),
+            Self::Conversion(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for AnalyticsError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Conversion(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ConversionError> for AnalyticsError {
+    fn from(e: ConversionError) -> Self {
+        AnalyticsError::Conversion(e)
+    }
+}
+
+/// A percentage, stored as an exact amount of basis points (hundredths of a percent)
+/// Metrics comparing prices return a Porcentaje, so it can't be mistaken for a price
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Porcentaje(i64);
+
+impl Porcentaje {
+    /// The amount of basis points in a percent
+    pub const SCALE: i64 = 100;
+
+    /// Returns a Porcentaje for the given amount of basis points (Ex: 2050 is 20.50%)
+    pub fn from_basis_points(basis_points: i64) -> Self {
+        Porcentaje(basis_points)
+    }
+
+    /// Returns the amount of basis points
+    pub fn basis_points(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns the percentage as a float (Ex: 20.5 for 20.50%)
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+
+    /// Returns the percentage as a ratio (Ex: 0.205 for 20.50%)
+    pub fn ratio(&self) -> f64 {
+        self.as_f64() / 100.0
+    }
+}
+
+impl fmt::Display for Porcentaje {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let value = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:02}%",
+            sign,
+            value / Self::SCALE as u64,
+            value % Self::SCALE as u64
+        )
+    }
+}
+
+/// A dimensionless ratio between two prices, stored as an exact amount of millionths
+/// Cross rates return a Razon, since dividing two prices doesn't give a price
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Razon(i64);
+
+impl Razon {
+    /// The amount of millionths in a unit
+    pub const SCALE: i64 = 1_000_000;
+
+    /// Returns a Razon for the given amount of millionths (Ex: 1025189 is 1.025189)
+    pub fn from_millionths(millionths: i64) -> Self {
+        Razon(millionths)
+    }
+
+    /// Returns the amount of millionths
+    pub fn millionths(&self) -> i64 {
+        self.0
+    }
+
+    /// Returns the ratio as a float (Ex: 1.025189)
+    pub fn as_f64(&self) -> f64 {
+        self.0 as f64 / Self::SCALE as f64
+    }
+}
+
+impl fmt::Display for Razon {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let value = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:06}",
+            sign,
+            value / Self::SCALE as u64,
+            value % Self::SCALE as u64
+        )
+    }
+}
+
+/// A set of quotes for different currency types, used to compute metrics across them
+/// Prices are rounded with the given Rounding (2 decimals by default)
+/// Percentages are rounded to basis points and cross rates to millionths, using the mode of the given Rounding
+///
+/// # Example
+///
+/// ```
+/// use dolarhoy_core::analytics::{Cotizaciones, Porcentaje};
+/// use dolarhoy_core::conversion::Side;
+/// use dolarhoy_core::dolar::Cotizacion;
+/// use dolarhoy_core::precio::Centavos;
+///
+/// let cotizaciones = Cotizaciones::new()
+///     .with(Cotizacion::Blue, (Centavos(100000), Some(Centavos(102000))))
+///     .with(Cotizacion::Oficial, (Centavos(80000), Some(Centavos(85000))));
+///
+/// // 1020 / 850 - 1 = 20%
+/// let brecha = cotizaciones.brecha(&Cotizacion::Blue, &Cotizacion::Oficial, Side::Venta);
+/// assert_eq!(brecha, Ok(Porcentaje::from_basis_points(2000)));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cotizaciones<T> {
+    precios: HashMap<Cotizacion, CompraVenta<T>>,
+    rounding: Rounding,
+}
+
+impl<T: Convertible> Cotizaciones<T> {
+    /// Returns an empty Cotizaciones instance
+    pub fn new() -> Self {
+        Cotizaciones {
+            precios: HashMap::new(),
+            rounding: Rounding::default(),
+        }
+    }
+
+    /// Sets the rounding applied to computed prices, and the rounding mode of percentages and rates
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Adds the buy/sell prices of a currency type, replacing any previous ones
+    pub fn with(mut self, cotizacion: Cotizacion, precio: CompraVenta<T>) -> Self {
+        self.insert(cotizacion, precio);
+        self
+    }
+
+    /// Adds the buy/sell prices of a currency type, replacing any previous ones
+    pub fn insert(&mut self, cotizacion: Cotizacion, precio: CompraVenta<T>) {
+        self.precios.insert(cotizacion, precio);
+    }
+
+    /// Adds a parsed quote, replacing any previous one
+    pub fn insert_precio(&mut self, cotizacion: Cotizacion, precio: &dyn PrecioCompraVenta<T>) {
+        self.insert(cotizacion, precio.precio_compra_venta());
+    }
+
+    /// Returns the buy/sell prices of a currency type
+    pub fn get(&self, cotizacion: &Cotizacion) -> Option<CompraVenta<T>> {
+        self.precios.get(cotizacion).copied()
+    }
+
+    /// Returns the price on the given side of a quote
+    pub fn precio(&self, cotizacion: &Cotizacion, side: Side) -> Result<T, AnalyticsError> {
+        let (compra, venta) = self.compra_venta(cotizacion)?;
+        match side {
+            Side::Compra => Ok(compra),
+            Side::Venta => venta.ok_or_else(|| AnalyticsError::MissingVenta(cotizacion.clone())),
+        }
+    }
+
+    /// Returns the average of the compra and venta prices
+    pub fn midpoint(&self, cotizacion: &Cotizacion) -> Result<T, AnalyticsError> {
+        let (compra, venta) = self.both(cotizacion)?;
+        Ok(compra
+            .add_precio(venta)?
+            .div_precio(T::from_units(2)?, self.rounding)?)
+    }
+
+    /// Returns the bid/ask spread (venta - compra)
+    pub fn spread(&self, cotizacion: &Cotizacion) -> Result<T, AnalyticsError> {
+        let (compra, venta) = self.both(cotizacion)?;
+        Ok(venta.sub_precio(compra)?)
+    }
+
+    /// Returns the bid/ask spread as a percentage of the midpoint
+    pub fn spread_percent(&self, cotizacion: &Cotizacion) -> Result<Porcentaje, AnalyticsError> {
+        let spread = self.spread(cotizacion)?;
+        let midpoint = self.midpoint(cotizacion)?;
+        self.percent(spread, midpoint)
+    }
+
+    /// Returns the brecha cambiaria: how much more expensive `paralelo` is than `oficial`, in percent
+    /// Both quotes are compared on the given side (the venta price is the usual reference)
+    pub fn brecha(
+        &self,
+        paralelo: &Cotizacion,
+        oficial: &Cotizacion,
+        side: Side,
+    ) -> Result<Porcentaje, AnalyticsError> {
+        self.same_par(paralelo, oficial)?;
+
+        let paralelo = self.precio(paralelo, side)?;
+        let oficial = self.precio(oficial, side)?;
+        self.percent(paralelo.sub_precio(oficial)?, oficial)
+    }
+
+    /// Returns the implied cross rate between two quotes: units of `quote` per unit of `base`
+    /// Computed from the midpoints (Ex: CCL / MEP gives the canje, Euro / Oficial gives EUR/USD)
+    pub fn cross_rate(
+        &self,
+        base: &Cotizacion,
+        quote: &Cotizacion,
+    ) -> Result<Razon, AnalyticsError> {
+        self.same_moneda(base, quote)?;
+
+        let base = self.midpoint(base)?;
+        let quote = self.midpoint(quote)?;
+        Ok(Razon(self.scaled(base, quote, Razon::SCALE)?))
+    }
+
+    fn compra_venta(&self, cotizacion: &Cotizacion) -> Result<CompraVenta<T>, AnalyticsError> {
+        self.get(cotizacion)
+            .ok_or_else(|| AnalyticsError::MissingCotizacion(cotizacion.clone()))
+    }
+
+    /// Returns both prices of a quote, failing if there's no venta
+    fn both(&self, cotizacion: &Cotizacion) -> Result<(T, T), AnalyticsError> {
+        match self.compra_venta(cotizacion)? {
+            (compra, Some(venta)) => Ok((compra, venta)),
+            (_, None) => Err(AnalyticsError::MissingVenta(cotizacion.clone())),
+        }
+    }
+
+    /// Returns `value` as a percentage of `total`
+    fn percent(&self, value: T, total: T) -> Result<Porcentaje, AnalyticsError> {
+        Ok(Porcentaje(self.scaled(
+            value,
+            total,
+            100 * Porcentaje::SCALE,
+        )?))
+    }
+
+    /// Returns `value / total`, as a whole amount of `1 / scale` units
+    fn scaled(&self, value: T, total: T, scale: i64) -> Result<i64, AnalyticsError> {
+        // Only the division rounds
+        let mode = self.rounding.mode();
+        Ok(value
+            .mul_precio(T::from_units(scale)?, Rounding::new(u32::MAX, mode))?
+            .div_precio(total, Rounding::new(0, mode))?
+            .to_units()?)
+    }
+
+    /// Checks both quotes price the same currency in the same currency (Ex: Blue and Oficial)
+    fn same_par(&self, a: &Cotizacion, b: &Cotizacion) -> Result<(), AnalyticsError> {
+        let (a, b) = (a.par(), b.par());
+        if a.base != b.base {
+            return Err(AnalyticsError::MonedaMismatch(a.base, b.base));
+        }
+        if a.quote != b.quote {
+            return Err(AnalyticsError::MonedaMismatch(a.quote, b.quote));
+        }
+        Ok(())
+    }
+
+    fn same_moneda(&self, a: &Cotizacion, b: &Cotizacion) -> Result<(), AnalyticsError> {
+        if a.moneda() != b.moneda() {
+            return Err(AnalyticsError::MonedaMismatch(a.moneda(), b.moneda()));
+        }
+        Ok(())
+    }
+}
+
+impl<T: Convertible> Default for Cotizaciones<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Convertible> FromIterator<(Cotizacion, CompraVenta<T>)> for Cotizaciones<T> {
+    fn from_iter<I: IntoIterator<Item = (Cotizacion, CompraVenta<T>)>>(iter: I) -> Self {
+        let mut cotizaciones = Cotizaciones::new();
+        for (cotizacion, precio) in iter {
+            cotizaciones.insert(cotizacion, precio);
+        }
+        cotizaciones
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::RoundingMode;
+    use crate::precio::Centavos;
+
+    fn cotizaciones() -> Cotizaciones<Centavos> {
+        Cotizaciones::from_iter([
+            (Cotizacion::Blue, (Centavos(100000), Some(Centavos(102000)))),
+            (
+                Cotizacion::Oficial,
+                (Centavos(80000), Some(Centavos(85000))),
+            ),
+            (Cotizacion::Bolsa, (Centavos(99000), Some(Centavos(99500)))),
+            (
+                Cotizacion::ContadoConLiqui,
+                (Centavos(101000), Some(Centavos(102500))),
+            ),
+            (Cotizacion::Tarjeta, (Centavos(136000), None)),
+            (Cotizacion::Crypto, (Centavos(3000000), None)),
+        ])
+    }
+
+    #[test]
+    fn test_brecha() {
+        let cotizaciones = cotizaciones();
+
+        // 1020 / 850 - 1
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Blue, &Cotizacion::Oficial, Side::Venta),
+            Ok(Porcentaje(2000))
+        );
+        // 1000 / 800 - 1
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Blue, &Cotizacion::Oficial, Side::Compra),
+            Ok(Porcentaje(2500))
+        );
+        // 1025 / 995 - 1 = 3.0150...%
+        assert_eq!(
+            cotizaciones.brecha(
+                &Cotizacion::ContadoConLiqui,
+                &Cotizacion::Bolsa,
+                Side::Venta
+            ),
+            Ok(Porcentaje(302))
+        );
+        // 850 / 1020 - 1 = -16.666...%
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Oficial, &Cotizacion::Blue, Side::Venta),
+            Ok(Porcentaje(-1667))
+        );
+    }
+
+    #[test]
+    fn test_midpoint_and_spread() {
+        let cotizaciones = cotizaciones();
+
+        assert_eq!(
+            cotizaciones.midpoint(&Cotizacion::Blue),
+            Ok(Centavos(101000))
+        );
+        assert_eq!(cotizaciones.spread(&Cotizacion::Blue), Ok(Centavos(2000)));
+        // 20 / 1010 = 1.9801...%
+        assert_eq!(
+            cotizaciones.spread_percent(&Cotizacion::Blue),
+            Ok(Porcentaje(198))
+        );
+    }
+
+    #[test]
+    fn test_cross_rate() {
+        let cotizaciones = cotizaciones();
+
+        // 1017.50 / 992.50 = 1.02518891...
+        let canje = cotizaciones.cross_rate(&Cotizacion::ContadoConLiqui, &Cotizacion::Bolsa);
+        assert_eq!(canje, Ok(Razon(1025189)));
+        assert_eq!(canje.unwrap().to_string(), "1.025189");
+
+        // 992.50 / 1017.50 = 0.97542997...
+        assert_eq!(
+            cotizaciones.cross_rate(&Cotizacion::Bolsa, &Cotizacion::ContadoConLiqui),
+            Ok(Razon(975430))
+        );
+
+        let cotizaciones: Cotizaciones<f64> = Cotizaciones::from_iter([
+            (Cotizacion::ContadoConLiqui, (1010.0, Some(1025.0))),
+            (Cotizacion::Bolsa, (990.0, Some(995.0))),
+        ])
+        .rounding(Rounding::new(4, RoundingMode::Down));
+        assert_eq!(
+            cotizaciones.cross_rate(&Cotizacion::ContadoConLiqui, &Cotizacion::Bolsa),
+            Ok(Razon(1025188))
+        );
+        // 1025 / 995 - 1 = 3.0150...%, rounded down
+        assert_eq!(
+            cotizaciones.brecha(
+                &Cotizacion::ContadoConLiqui,
+                &Cotizacion::Bolsa,
+                Side::Venta
+            ),
+            Ok(Porcentaje(301))
+        );
+    }
+
+    #[test]
+    fn test_porcentaje() {
+        let porcentaje = Porcentaje::from_basis_points(2050);
+        assert_eq!(porcentaje.basis_points(), 2050);
+        assert_eq!(porcentaje.as_f64(), 20.5);
+        assert_eq!(porcentaje.ratio(), 0.205);
+        assert_eq!(porcentaje.to_string(), "20.50%");
+        assert_eq!(Porcentaje(-1667).to_string(), "-16.67%");
+        assert_eq!(Porcentaje(5).to_string(), "0.05%");
+    }
+
+    #[test]
+    fn test_razon() {
+        let razon = Razon::from_millionths(1025189);
+        assert_eq!(razon.millionths(), 1025189);
+        assert_eq!(razon.as_f64(), 1.025189);
+        assert_eq!(Razon(5).to_string(), "0.000005");
+        assert_eq!(Razon(-1500000).to_string(), "-1.500000");
+    }
+
+    #[test]
+    fn test_errors() {
+        let cotizaciones = cotizaciones();
+
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Solidario, &Cotizacion::Oficial, Side::Venta),
+            Err(AnalyticsError::MissingCotizacion(Cotizacion::Solidario))
+        );
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Tarjeta, &Cotizacion::Oficial, Side::Venta),
+            Err(AnalyticsError::MissingVenta(Cotizacion::Tarjeta))
+        );
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Tarjeta, &Cotizacion::Oficial, Side::Compra),
+            Ok(Porcentaje(7000))
+        );
+        assert_eq!(
+            cotizaciones.midpoint(&Cotizacion::Tarjeta),
+            Err(AnalyticsError::MissingVenta(Cotizacion::Tarjeta))
+        );
+        assert_eq!(
+            cotizaciones.cross_rate(&Cotizacion::Crypto, &Cotizacion::Blue),
+            Err(AnalyticsError::MonedaMismatch(Moneda::USD, Moneda::ARS))
+        );
+        assert_eq!(
+            cotizaciones.brecha(&Cotizacion::Euro, &Cotizacion::Oficial, Side::Venta),
+            Err(AnalyticsError::MonedaMismatch(Moneda::EUR, Moneda::USD))
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use crate::client::header::HeaderMap;
+use crate::client::{self, DolayHoyClientBuilder, PrecioType};
+use crate::dolar::Cotizacion;
+use crate::error::ClientError;
+use crate::parser::{PrecioCompraVenta, Snapshot, Widget};
+use std::io;
+use tokio::runtime::{Builder, Runtime};
+
+/// A synchronous client for fetching currency prices
+/// Requests are run on an internal single-threaded runtime
+///
+/// Don't use it from within an async runtime, use client::DolayHoyClient instead
+pub struct DolayHoyClient {
+    inner: client::DolayHoyClient,
+    runtime: Runtime,
+}
+
+impl DolayHoyClient {
+    /// Returns a new DolayHoyClient instance
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime can't be created, use try_from_client to handle the error
+    pub fn new() -> Self {
+        Self::from_client(client::DolayHoyClient::new())
+    }
+
+    /// Returns a DolayHoyClientBuilder for customizing the client
+    /// Pass the built client to from_client to get a blocking one
+    pub fn builder() -> DolayHoyClientBuilder {
+        DolayHoyClientBuilder::new()
+    }
+
+    /// Returns a blocking client wrapping the given async client
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal runtime can't be created, use try_from_client to handle the error
+    pub fn from_client(client: client::DolayHoyClient) -> Self {
+        Self::try_from_client(client).expect("failed to create the runtime")
+    }
+
+    /// Returns a blocking client wrapping the given async client
+    pub fn try_from_client(client: client::DolayHoyClient) -> io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(DolayHoyClient {
+            inner: client,
+            runtime,
+        })
+    }
+
+    /// Fetches the prices of the given currency
+    /// Returns a Result wrapping a Box<dyn parser::PrecioCompraVenta<T>>
+    /// Where T is any PrecioType: f32, f64, precio::Centavos or precio::Decimal (with the decimal feature)
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use dolarhoy_core::{blocking, dolar};
+    ///
+    /// let client = blocking::DolayHoyClient::new();
+    /// let price = client.fetch_cotizacion::<f32>(dolar::Cotizacion::Oficial).unwrap();
+    /// let (compra, venta) = price.precio_compra_venta();
+    /// println!("Buy: {} / Sale: {}", compra, venta.unwrap());
+    /// ```
+    pub fn fetch_cotizacion<T: PrecioType>(
+        &self,
+        cotizacion: Cotizacion,
+    ) -> Result<Box<dyn PrecioCompraVenta<T>>, ClientError> {
+        self.runtime
+            .block_on(self.inner.fetch_cotizacion(cotizacion))
+    }
+
+    /// Fetches the prices of the given currency, sending additional headers
+    /// Headers given here replace the ones set on the builder with the same name
+    pub fn fetch_cotizacion_with_headers<T: PrecioType>(
+        &self,
+        cotizacion: Cotizacion,
+        headers: &HeaderMap,
+    ) -> Result<Box<dyn PrecioCompraVenta<T>>, ClientError> {
+        self.runtime.block_on(
+            self.inner
+                .fetch_cotizacion_with_headers(cotizacion, headers),
+        )
+    }
+
+    /// Fetches the prices of all the given currencies concurrently
+    /// Results are returned in the same order as the given currencies
+    pub fn fetch_cotizaciones<T: PrecioType>(
+        &self,
+        cotizaciones: &[Cotizacion],
+    ) -> Vec<Result<Box<dyn PrecioCompraVenta<T>>, ClientError>> {
+        self.runtime
+            .block_on(self.inner.fetch_cotizaciones(cotizaciones))
+    }
+
+    /// Fetches the prices of the given currency without waiting for the rate limiter
+    /// Returns ClientError::RateLimited if the request can't be made right away
+    pub fn try_fetch_cotizacion<T: PrecioType>(
+        &self,
+        cotizacion: Cotizacion,
+    ) -> Result<Box<dyn PrecioCompraVenta<T>>, ClientError> {
+        self.runtime
+            .block_on(self.inner.try_fetch_cotizacion(cotizacion))
+    }
+
+    /// Lists the quotes linked from the dolarhoy.com homepage, along with their titles
+    pub fn discover_cotizaciones(&self) -> Result<Vec<Widget>, ClientError> {
+        self.runtime.block_on(self.inner.discover_cotizaciones())
+    }
+
+    /// Fetches every quote shown on the dolarhoy.com homepage with a single request
+    pub fn fetch_homepage_snapshot<T: PrecioType>(&self) -> Result<Snapshot<T>, ClientError> {
+        self.runtime.block_on(self.inner.fetch_homepage_snapshot())
+    }
+
+    /// Returns the async client used to make the requests
+    pub fn inner(&self) -> &client::DolayHoyClient {
+        &self.inner
+    }
+}
+
+impl Default for DolayHoyClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<client::DolayHoyClient> for DolayHoyClient {
+    fn from(client: client::DolayHoyClient) -> Self {
+        Self::from_client(client)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::StaticResolver;
+    use crate::testing::serve;
+
+    #[test]
+    fn test_blocking_fetch() {
+        let (addr, tls_config) = serve(2);
+        let client = DolayHoyClient::from_client(
+            DolayHoyClient::builder()
+                .resolver(StaticResolver::new().host("dolarhoy.com", vec![addr]))
+                .tls_config(tls_config)
+                .build(),
+        );
+
+        let precio = client.fetch_cotizacion::<f64>(Cotizacion::Blue).unwrap();
+        assert_eq!(precio.title(), "Dólar Blue");
+        assert_eq!(precio.precio_compra_venta(), (566.00, Some(571.00)));
+
+        let precio = client
+            .try_fetch_cotizacion::<f64>(Cotizacion::Blue)
+            .unwrap();
+        assert_eq!(precio.precio_compra_venta(), (566.00, Some(571.00)));
+    }
+
+    #[test]
+    fn test_blocking_fetch_error() {
+        let client = DolayHoyClient::from_client(
+            DolayHoyClient::builder()
+                .resolver(StaticResolver::new())
+                .build(),
+        );
+
+        let result = client.fetch_cotizacion::<f64>(Cotizacion::Blue);
+        assert!(matches!(result, Err(ClientError::RequestError(_))));
+
+        let results = client.fetch_cotizaciones::<f64>(&[Cotizacion::Blue, Cotizacion::Oficial]);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|r| r.is_err()));
+    }
+}
@@ -0,0 +1,509 @@
+use crate::error::ClientError;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// A callback invoked on every circuit state transition, with the old and new states
+pub type StateChangeCallback = Arc<dyn Fn(CircuitState, CircuitState) + Send + Sync>;
+
+/// An enum describing the state of the circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests go through normally
+    Closed,
+    /// Requests fail fast until the given instant
+    Open { until: std::time::Instant },
+    /// A limited amount of probe requests are let through
+    HalfOpen,
+}
+
+/// Opens the circuit when the error rate in a time window goes above a threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ErrorRate {
+    /// Error rate, between 0.0 and 1.0
+    pub threshold: f64,
+    /// Time window considered
+    pub window: Duration,
+    /// Minimum amount of requests in the window before the rate is considered
+    pub min_requests: u32,
+}
+
+/// Settings for the circuit breaker
+#[derive(Clone)]
+pub struct CircuitBreakerConfig {
+    failure_threshold: u32,
+    error_rate: Option<ErrorRate>,
+    open_duration: Duration,
+    half_open_probes: u32,
+    on_state_change: Option<StateChangeCallback>,
+}
+
+impl CircuitBreakerConfig {
+    /// Returns the default settings
+    /// Opens after 5 consecutive failures, for 30 seconds, with a single probe
+    pub fn new() -> Self {
+        CircuitBreakerConfig {
+            failure_threshold: 5,
+            error_rate: None,
+            open_duration: Duration::from_secs(30),
+            half_open_probes: 1,
+            on_state_change: None,
+        }
+    }
+
+    /// Sets the amount of consecutive failures that opens the circuit
+    pub fn failure_threshold(mut self, failures: u32) -> Self {
+        self.failure_threshold = failures.max(1);
+        self
+    }
+
+    /// Opens the circuit when the error rate in the window goes above the threshold
+    pub fn error_rate(mut self, threshold: f64, window: Duration, min_requests: u32) -> Self {
+        self.error_rate = Some(ErrorRate {
+            threshold,
+            window,
+            min_requests: min_requests.max(1),
+        });
+        self
+    }
+
+    /// Sets how long the circuit stays open before letting probes through
+    pub fn open_duration(mut self, duration: Duration) -> Self {
+        self.open_duration = duration;
+        self
+    }
+
+    /// Sets the amount of successful probes needed to close the circuit again
+    /// This is also the maximum amount of probes in flight at the same time
+    pub fn half_open_probes(mut self, probes: u32) -> Self {
+        self.half_open_probes = probes.max(1);
+        self
+    }
+
+    /// Sets a callback invoked on every state transition
+    pub fn on_state_change<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(CircuitState, CircuitState) + Send + Sync + 'static,
+    {
+        self.on_state_change = Some(Arc::new(callback));
+        self
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for CircuitBreakerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreakerConfig")
+            .field("failure_threshold", &self.failure_threshold)
+            .field("error_rate", &self.error_rate)
+            .field("open_duration", &self.open_duration)
+            .field("half_open_probes", &self.half_open_probes)
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+enum Phase {
+    Closed,
+    Open { until: Instant },
+    HalfOpen { in_flight: u32, successes: u32 },
+}
+
+#[derive(Debug)]
+struct Breaker {
+    phase: Phase,
+    consecutive_failures: u32,
+    outcomes: VecDeque<(Instant, bool)>,
+}
+
+/// A circuit breaker guarding requests to dolarhoy.com
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<Breaker>,
+}
+
+/// Returns true if the error means the endpoint is failing: transport errors (timeouts included) and 5xx responses
+/// A maintenance page counts as a 5xx, while parse errors, challenges and unexpected content types don't,
+/// so a change to the page layout doesn't block the quotes that still parse
+fn is_failure(error: &ClientError) -> bool {
+    match error {
+        ClientError::RequestError(_)
+        | ClientError::ProxyError(_)
+        | ClientError::MaintenancePage => true,
+        ClientError::ResponseStatusError(status) => (500..600).contains(status),
+        _ => false,
+    }
+}
+
+fn to_state(phase: &Phase) -> CircuitState {
+    match phase {
+        Phase::Closed => CircuitState::Closed,
+        Phase::Open { until } => CircuitState::Open {
+            until: until.into_std(),
+        },
+        Phase::HalfOpen { .. } => CircuitState::HalfOpen,
+    }
+}
+
+impl CircuitBreaker {
+    pub(crate) fn new(config: CircuitBreakerConfig) -> Self {
+        CircuitBreaker {
+            config,
+            state: Mutex::new(Breaker {
+                phase: Phase::Closed,
+                consecutive_failures: 0,
+                outcomes: VecDeque::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Breaker> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the current state
+    pub(crate) fn state(&self) -> CircuitState {
+        to_state(&self.lock().phase)
+    }
+
+    /// Moves to a new phase and returns the transition, if the state changed
+    fn transition(
+        &self,
+        breaker: &mut Breaker,
+        phase: Phase,
+    ) -> Option<(CircuitState, CircuitState)> {
+        let from = to_state(&breaker.phase);
+        let to = to_state(&phase);
+        breaker.phase = phase;
+
+        if matches!(breaker.phase, Phase::Closed | Phase::Open { .. }) {
+            breaker.consecutive_failures = 0;
+            breaker.outcomes.clear();
+        }
+
+        (from != to).then_some((from, to))
+    }
+
+    fn notify(&self, transition: Option<(CircuitState, CircuitState)>) {
+        if let (Some((from, to)), Some(callback)) = (transition, &self.config.on_state_change) {
+            callback(from, to);
+        }
+    }
+
+    /// Checks whether a request can be made
+    /// Returns ClientError::CircuitOpen while the circuit is open
+    pub(crate) fn acquire(&self) -> Result<Probe<'_>, ClientError> {
+        let mut breaker = self.lock();
+        let now = Instant::now();
+
+        let mut transition = None;
+        if let Phase::Open { until } = breaker.phase {
+            if now < until {
+                return Err(ClientError::CircuitOpen {
+                    until: until.into_std(),
+                });
+            }
+
+            transition = self.transition(
+                &mut breaker,
+                Phase::HalfOpen {
+                    in_flight: 0,
+                    successes: 0,
+                },
+            );
+        }
+
+        let probe = match &mut breaker.phase {
+            Phase::HalfOpen { in_flight, .. } => {
+                if *in_flight >= self.config.half_open_probes {
+                    return Err(ClientError::CircuitOpen {
+                        until: now.into_std(),
+                    });
+                }
+                *in_flight += 1;
+                true
+            }
+            _ => false,
+        };
+
+        drop(breaker);
+        self.notify(transition);
+
+        Ok(Probe {
+            breaker: self,
+            probe,
+            done: false,
+        })
+    }
+
+    fn record(&self, probe: bool, failed: bool) {
+        let mut guard = self.lock();
+        let breaker = &mut *guard;
+        let now = Instant::now();
+        let open = Phase::Open {
+            until: now + self.config.open_duration,
+        };
+
+        let next = match &mut breaker.phase {
+            Phase::HalfOpen {
+                in_flight,
+                successes,
+            } if probe => {
+                *in_flight = in_flight.saturating_sub(1);
+                *successes += u32::from(!failed);
+
+                if failed {
+                    Some(open)
+                } else if *successes >= self.config.half_open_probes {
+                    Some(Phase::Closed)
+                } else {
+                    None
+                }
+            }
+            Phase::Closed => {
+                breaker.consecutive_failures = match failed {
+                    true => breaker.consecutive_failures + 1,
+                    false => 0,
+                };
+
+                let mut trip = breaker.consecutive_failures >= self.config.failure_threshold;
+
+                if let Some(rate) = &self.config.error_rate {
+                    breaker.outcomes.push_back((now, failed));
+                    while breaker
+                        .outcomes
+                        .front()
+                        .is_some_and(|(at, _)| now.duration_since(*at) > rate.window)
+                    {
+                        breaker.outcomes.pop_front();
+                    }
+
+                    let total = breaker.outcomes.len();
+                    let failures = breaker.outcomes.iter().filter(|(_, f)| *f).count();
+                    if total >= rate.min_requests as usize
+                        && failures as f64 / total as f64 >= rate.threshold
+                    {
+                        trip = true;
+                    }
+                }
+
+                trip.then_some(open)
+            }
+            // Outcomes of requests started before the circuit opened are ignored
+            _ => None,
+        };
+
+        let transition = next.and_then(|phase| self.transition(breaker, phase));
+        drop(guard);
+        self.notify(transition);
+    }
+
+    /// Releases a probe slot without recording an outcome
+    fn release(&self) {
+        if let Phase::HalfOpen { in_flight, .. } = &mut self.lock().phase {
+            *in_flight = in_flight.saturating_sub(1);
+        }
+    }
+}
+
+/// A pass granted by the circuit breaker, used to report the request outcome
+/// Dropping it without calling record (Ex: a cancelled request) frees the probe slot
+pub(crate) struct Probe<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+    done: bool,
+}
+
+impl Probe<'_> {
+    /// Records the outcome of the request
+    pub(crate) fn record<T>(mut self, result: &Result<T, ClientError>) {
+        self.done = true;
+        let failed = result.as_ref().err().is_some_and(is_failure);
+        self.breaker.record(self.probe, failed);
+    }
+}
+
+impl Drop for Probe<'_> {
+    fn drop(&mut self) {
+        if !self.done && self.probe {
+            self.breaker.release();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    fn failure() -> Result<(), ClientError> {
+        Err(ClientError::ResponseStatusError(500))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_opens_after_consecutive_failures() {
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(3)
+                .open_duration(Duration::from_secs(10)),
+        );
+
+        breaker.acquire().unwrap().record(&failure());
+        breaker.acquire().unwrap().record(&failure());
+        breaker.acquire().unwrap().record(&Ok(()));
+        breaker.acquire().unwrap().record(&failure());
+        breaker.acquire().unwrap().record(&failure());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.acquire().unwrap().record(&failure());
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+        assert!(matches!(
+            breaker.acquire(),
+            Err(ClientError::CircuitOpen { .. })
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ignores_client_side_errors() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().failure_threshold(1));
+
+        breaker
+            .acquire()
+            .unwrap()
+            .record::<()>(&Err(ClientError::RateLimited));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_ignores_content_errors() {
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().failure_threshold(1));
+
+        let errors = [
+            ClientError::ParseError(unhtml::Error::SourceNotFound),
+            ClientError::BlockedByChallenge,
+            ClientError::UnexpectedContentType("application/json".to_string()),
+            ClientError::ResponseStatusError(404),
+        ];
+        for error in errors {
+            breaker.acquire().unwrap().record::<()>(&Err(error));
+            assert_eq!(breaker.state(), CircuitState::Closed);
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_counts_transport_errors() {
+        let errors = [
+            ClientError::RequestError(io::Error::from(io::ErrorKind::TimedOut)),
+            ClientError::ProxyError("connection refused".to_string()),
+            ClientError::ResponseStatusError(503),
+            ClientError::MaintenancePage,
+        ];
+        for error in errors {
+            let breaker = CircuitBreaker::new(CircuitBreakerConfig::new().failure_threshold(1));
+            breaker.acquire().unwrap().record::<()>(&Err(error));
+            assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_opens_on_error_rate() {
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(100)
+                .error_rate(0.5, Duration::from_secs(60), 4),
+        );
+
+        breaker.acquire().unwrap().record(&failure());
+        breaker.acquire().unwrap().record(&Ok(()));
+        breaker.acquire().unwrap().record(&failure());
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        breaker.acquire().unwrap().record(&Ok(()));
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_error_rate_window() {
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(100)
+                .error_rate(0.5, Duration::from_secs(60), 2),
+        );
+
+        breaker.acquire().unwrap().record(&failure());
+        tokio::time::advance(Duration::from_secs(61)).await;
+        breaker.acquire().unwrap().record(&Ok(()));
+        breaker.acquire().unwrap().record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_half_open_probes() {
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let recorded = transitions.clone();
+
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(1)
+                .open_duration(Duration::from_secs(10))
+                .half_open_probes(2)
+                .on_state_change(move |from, to| recorded.lock().unwrap().push((from, to))),
+        );
+
+        breaker.acquire().unwrap().record(&failure());
+        tokio::time::advance(Duration::from_secs(10)).await;
+
+        let first = breaker.acquire().unwrap();
+        let second = breaker.acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.acquire().is_err());
+
+        // A cancelled probe frees its slot
+        drop(second);
+        let second = breaker.acquire().unwrap();
+
+        first.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        second.record(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let transitions = transitions.lock().unwrap();
+        assert_eq!(transitions.len(), 3);
+        assert!(matches!(
+            transitions[0],
+            (CircuitState::Closed, CircuitState::Open { .. })
+        ));
+        assert!(matches!(
+            transitions[1],
+            (CircuitState::Open { .. }, CircuitState::HalfOpen)
+        ));
+        assert_eq!(
+            transitions[2],
+            (CircuitState::HalfOpen, CircuitState::Closed)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_probe_reopens() {
+        let breaker = CircuitBreaker::new(
+            CircuitBreakerConfig::new()
+                .failure_threshold(1)
+                .open_duration(Duration::from_secs(10)),
+        );
+
+        breaker.acquire().unwrap().record(&failure());
+        tokio::time::advance(Duration::from_secs(10)).await;
+
+        breaker.acquire().unwrap().record(&failure());
+        assert!(matches!(breaker.state(), CircuitState::Open { .. }));
+        assert!(breaker.acquire().is_err());
+    }
+}
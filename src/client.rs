@@ -1,38 +1,386 @@
+use crate::breaker::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::connect;
 use crate::dolar::{self, Cotizacion};
 use crate::error::ClientError;
+use crate::hedge::{HedgePolicy, Hedger};
+use crate::limit::{HostLimits, Limiter, RateLimit};
+use crate::observer::{self, ClientObserver};
 use crate::parser;
+use crate::precio::Centavos;
+use crate::proxy::{NoProxy, Proxy};
+use crate::resolver::{self, GaiResolver, Resolve};
+use crate::runtime::{self, Io, Runtime};
+use crate::tls::TlsOptions;
+use futures_util::future::join_all;
+use header::{HeaderMap, HeaderName, HeaderValue};
+use std::collections::HashMap;
+use std::future::Future;
 use std::io;
-use std::net::ToSocketAddrs;
+use std::net::SocketAddr;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::io::{split, AsyncReadExt, AsyncWriteExt};
-use tokio::net::TcpStream;
+use std::time::{Duration, Instant};
+use tokio::io::{split, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::OwnedSemaphorePermit;
+use tokio_rustls::client::TlsStream;
 use tokio_rustls::rustls::{self, pki_types};
 use tokio_rustls::TlsConnector;
 use unhtml::FromHtml;
 
+/// The header types taken by the builder and fetch_cotizacion_with_headers
+pub use hyper::header;
+
 /// A trait for restricting which values can be used to pull prices
+/// Implemented for f32, f64, precio::Centavos and precio::Decimal (with the decimal feature)
 pub trait PrecioType: Send + Copy + FromStr + 'static {}
 
 impl PrecioType for f32 {}
 impl PrecioType for f64 {}
+impl PrecioType for Centavos {}
+
+#[cfg(feature = "decimal")]
+impl PrecioType for crate::precio::Decimal {}
+
+/// The maximum response size accepted when none is configured
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+
+/// The User-Agent sent when none is configured
+pub const DEFAULT_USER_AGENT: &str = concat!("dolarhoy-core/", env!("CARGO_PKG_VERSION"));
+
+/// A builder for configuring a DolayHoyClient
+pub struct DolayHoyClientBuilder {
+    runtime: Arc<dyn Runtime>,
+    resolver: Option<Arc<dyn Resolve>>,
+    happy_eyeballs_delay: Duration,
+    proxy: Option<Proxy>,
+    no_proxy: NoProxy,
+    tls_config: Arc<rustls::ClientConfig>,
+    headers: HeaderMap,
+    limits: HashMap<String, HostLimits>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    hedge: Option<HedgePolicy>,
+    max_response_size: usize,
+    observer: Option<Arc<dyn ClientObserver>>,
+}
+
+impl DolayHoyClientBuilder {
+    /// Returns a new DolayHoyClientBuilder with default settings
+    pub fn new() -> Self {
+        DolayHoyClientBuilder {
+            runtime: runtime::default_runtime(),
+            resolver: None,
+            happy_eyeballs_delay: connect::DEFAULT_HAPPY_EYEBALLS_DELAY,
+            proxy: None,
+            no_proxy: NoProxy::default(),
+            tls_config: Arc::new(
+                rustls::ClientConfig::builder()
+                    .with_root_certificates(rustls::RootCertStore {
+                        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+                    })
+                    .with_no_client_auth(),
+            ),
+            headers: HeaderMap::from_iter([(
+                header::USER_AGENT,
+                HeaderValue::from_static(DEFAULT_USER_AGENT),
+            )]),
+            limits: HashMap::new(),
+            circuit_breaker: None,
+            hedge: None,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            observer: None,
+        }
+    }
+
+    /// Sets the async runtime used for sockets and timers
+    /// Defaults to tokio, or the only runtime feature enabled
+    pub fn runtime<R: Runtime + 'static>(mut self, runtime: R) -> Self {
+        self.runtime = Arc::new(runtime);
+        self
+    }
+
+    /// Sets the resolver used to look up the dolarhoy.com addresses
+    /// Defaults to a GaiResolver running lookups on the client's runtime
+    pub fn resolver<R: Resolve + 'static>(mut self, resolver: R) -> Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Sets the delay before starting a connection attempt on the next address
+    pub fn happy_eyeballs_delay(mut self, delay: Duration) -> Self {
+        self.happy_eyeballs_delay = delay;
+        self
+    }
+
+    /// Sets the proxy used to reach dolarhoy.com
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Sets the hosts that must be reached without going through the proxy
+    pub fn no_proxy(mut self, no_proxy: NoProxy) -> Self {
+        self.no_proxy = no_proxy;
+        self
+    }
+
+    /// Reads the proxy settings from the HTTPS_PROXY and NO_PROXY environment variables
+    /// Returns an error if HTTPS_PROXY is set but can't be parsed
+    pub fn proxy_from_env(mut self) -> Result<Self, ClientError> {
+        self.proxy = Proxy::from_env()?;
+        self.no_proxy = NoProxy::from_env();
+        Ok(self)
+    }
+
+    /// Sets the TLS configuration from a TlsOptions instance
+    /// Returns an error if the options can't be turned into a valid configuration
+    pub fn tls_options(mut self, options: TlsOptions) -> Result<Self, ClientError> {
+        self.tls_config = Arc::new(options.client_config()?);
+        Ok(self)
+    }
+
+    /// Sets a user-supplied rustls::ClientConfig, replacing the default one
+    pub fn tls_config(mut self, config: rustls::ClientConfig) -> Self {
+        self.tls_config = Arc::new(config);
+        self
+    }
+
+    /// Sets the User-Agent header, replacing the default one
+    pub fn user_agent(self, value: HeaderValue) -> Self {
+        self.header(header::USER_AGENT, value)
+    }
+
+    /// Sets the Accept-Language header
+    pub fn accept_language(self, value: HeaderValue) -> Self {
+        self.header(header::ACCEPT_LANGUAGE, value)
+    }
+
+    /// Sets a header sent on every request, replacing any previous value
+    /// The Host header is managed by the client and can't be replaced
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Sets the rate limit for requests to dolarhoy.com
+    pub fn rate_limit(mut self, rate: RateLimit) -> Self {
+        self.limits
+            .entry(dolar::DOLAR_HOY_DOMAIN.to_string())
+            .or_default()
+            .rate = Some(rate);
+        self
+    }
+
+    /// Sets the maximum amount of concurrent requests to dolarhoy.com
+    pub fn max_in_flight(mut self, max: usize) -> Self {
+        self.limits
+            .entry(dolar::DOLAR_HOY_DOMAIN.to_string())
+            .or_default()
+            .max_in_flight = Some(max);
+        self
+    }
+
+    /// Sets the limits for requests going through the given host
+    /// Applies to dolarhoy.com and to the proxy host, if one is used
+    pub fn host_limits(mut self, host: &str, limits: HostLimits) -> Self {
+        self.limits.insert(host.to_ascii_lowercase(), limits);
+        self
+    }
+
+    /// Enables the circuit breaker around the dolarhoy.com endpoint
+    pub fn circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    /// Enables request hedging
+    pub fn hedge(mut self, policy: HedgePolicy) -> Self {
+        self.hedge = Some(policy);
+        self
+    }
+
+    /// Sets the maximum response size in bytes, headers included
+    /// Larger responses fail with ClientError::ResponseTooLarge
+    pub fn max_response_size(mut self, size: usize) -> Self {
+        self.max_response_size = size;
+        self
+    }
+
+    /// Sets an observer notified of every request (Ex: metrics::MetricsObserver)
+    pub fn observer<O: ClientObserver + 'static>(mut self, observer: O) -> Self {
+        self.observer = Some(Arc::new(observer));
+        self
+    }
+
+    /// Returns a DolayHoyClient using this configuration
+    pub fn build(self) -> DolayHoyClient {
+        let runtime = self.runtime;
+
+        DolayHoyClient {
+            resolver: self
+                .resolver
+                .unwrap_or_else(|| Arc::new(GaiResolver::with_runtime(runtime.clone()))),
+            happy_eyeballs_delay: self.happy_eyeballs_delay,
+            proxy: self.proxy,
+            no_proxy: self.no_proxy,
+            tls_config: self.tls_config,
+            headers: self.headers,
+            limiters: Arc::new(
+                self.limits
+                    .into_iter()
+                    .map(|(host, limits)| (host, Limiter::new(limits, runtime.clone())))
+                    .collect(),
+            ),
+            breaker: self
+                .circuit_breaker
+                .map(|config| Arc::new(CircuitBreaker::new(config))),
+            hedger: self.hedge.map(|policy| Arc::new(Hedger::new(policy))),
+            max_response_size: self.max_response_size,
+            observer: self.observer,
+            runtime,
+        }
+    }
+}
+
+impl Default for DolayHoyClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// A client for fetching currency prices
-pub struct DolayHoyClient {}
+#[derive(Clone)]
+pub struct DolayHoyClient {
+    resolver: Arc<dyn Resolve>,
+    happy_eyeballs_delay: Duration,
+    proxy: Option<Proxy>,
+    no_proxy: NoProxy,
+    tls_config: Arc<rustls::ClientConfig>,
+    headers: HeaderMap,
+    limiters: Arc<HashMap<String, Limiter>>,
+    breaker: Option<Arc<CircuitBreaker>>,
+    hedger: Option<Arc<Hedger>>,
+    max_response_size: usize,
+    observer: Option<Arc<dyn ClientObserver>>,
+    runtime: Arc<dyn Runtime>,
+}
 
 impl DolayHoyClient {
     /// Returns a new DolayHoyClient instance
     pub fn new() -> Self {
-        DolayHoyClient {}
+        DolayHoyClientBuilder::new().build()
+    }
+
+    /// Returns a DolayHoyClientBuilder for customizing the client
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dolarhoy_core::{client, resolver};
+    /// use std::net::SocketAddr;
+    ///
+    /// let addr: SocketAddr = "190.210.0.1:443".parse().unwrap();
+    /// let client = client::DolayHoyClient::builder()
+    ///     .resolver(resolver::StaticResolver::new().host("dolarhoy.com", vec![addr]))
+    ///     .build();
+    /// ```
+    pub fn builder() -> DolayHoyClientBuilder {
+        DolayHoyClientBuilder::new()
+    }
+
+    /// Returns a tower::Service fetching prices with this client
+    #[cfg(feature = "tower")]
+    pub fn service<T: PrecioType>(&self) -> crate::service::CotizacionService<T> {
+        crate::service::CotizacionService::new(self.clone())
+    }
+
+    /// Returns the state of the circuit breaker, if enabled
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.breaker.as_ref().map(|breaker| breaker.state())
+    }
+
+    /// Returns the proxy used to reach dolarhoy.com, unless NO_PROXY excludes it
+    fn route_proxy(&self) -> Option<&Proxy> {
+        self.proxy
+            .as_ref()
+            .filter(|_| !self.no_proxy.matches(dolar::DOLAR_HOY_DOMAIN))
+    }
+
+    /// Opens a connection to dolarhoy.com, tunneling through the proxy if there's one
+    async fn connect(&self) -> Result<Box<dyn Io>, ClientError> {
+        let domain = dolar::DOLAR_HOY_DOMAIN;
+
+        match self.route_proxy() {
+            Some(proxy) => {
+                let target = match proxy.resolves_locally() {
+                    true => match self.resolve(domain, 443).await?.first() {
+                        Some(addr) => addr.ip().to_string(),
+                        None => {
+                            return Err(ClientError::ProxyError(
+                                "no address for target".to_string(),
+                            ))
+                        }
+                    },
+                    false => domain.to_owned(),
+                };
+                let stream = self.connect_to(proxy.host(), proxy.port()).await?;
+                proxy.tunnel(stream, &target, 443).await
+            }
+            None => Ok(self.connect_to(domain, 443).await?),
+        }
+    }
+
+    /// Resolves the given host and connects to the first address that answers
+    async fn connect_to(&self, host: &str, port: u16) -> io::Result<Box<dyn Io>> {
+        let addrs = self.resolve(host, port).await?;
+        connect::happy_eyeballs(
+            &*self.runtime,
+            resolver::interleave(addrs),
+            self.happy_eyeballs_delay,
+        )
+        .await
+    }
+
+    /// Returns all addresses for the given host
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "dns",
+            skip(self),
+            fields(addrs = tracing::field::Empty, elapsed_ms = tracing::field::Empty),
+            err
+        )
+    )]
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        let start = Instant::now();
+        let addrs = self.resolver.resolve(host, port).await?;
+        record!("addrs", addrs.len());
+        record_elapsed!(start);
+        Ok(addrs)
+    }
+
+    /// Performs the TLS handshake with dolarhoy.com
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "tls", skip_all, fields(elapsed_ms = tracing::field::Empty), err)
+    )]
+    async fn handshake(&self, stream: Box<dyn Io>) -> io::Result<TlsStream<Box<dyn Io>>> {
+        let start = Instant::now();
+        let connector = TlsConnector::from(self.tls_config.clone());
+        let domain = pki_types::ServerName::try_from(dolar::DOLAR_HOY_DOMAIN)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid dnsname"))?;
+
+        let stream = connector.connect(domain, stream).await?;
+        record_elapsed!(start);
+        Ok(stream)
     }
 
     /// Fetches the prices of the given currency
     /// Returns a Result wrapping a Box<dyn parser::PrecioCompraVenta<T>>
-    /// Where T could be either be f32 o f64
+    /// Where T is any PrecioType: f32, f64, precio::Centavos or precio::Decimal (with the decimal feature)
     ///
     /// # Example
     ///
-    /// ```
+    /// ```no_run
     /// use dolarhoy_core::{client, dolar, error};
     ///
     /// type Result<T> = std::result::Result<T, error::ClientError>;
@@ -57,64 +405,762 @@ impl DolayHoyClient {
         &self,
         cotizacion: dolar::Cotizacion,
     ) -> Result<Box<dyn parser::PrecioCompraVenta<T>>, ClientError> {
-        use ClientError::*;
+        self.fetch_cotizacion_with_headers(cotizacion, &HeaderMap::new())
+            .await
+    }
+
+    /// Fetches the prices of the given currency, sending additional headers
+    /// Headers given here replace the ones set on the builder with the same name
+    pub async fn fetch_cotizacion_with_headers<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+        headers: &HeaderMap,
+    ) -> Result<Box<dyn parser::PrecioCompraVenta<T>>, ClientError> {
+        Ok(self.guarded(cotizacion, headers, true).await?)
+    }
 
-        let addr = (dolar::DOLAR_HOY_DOMAIN, 443)
-            .to_socket_addrs()?
-            .next()
-            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+    /// Fetches the prices of the given currency as a Send box, used by the tower::Service
+    #[cfg(feature = "tower")]
+    pub(crate) async fn fetch_cotizacion_send<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+    ) -> Result<Box<dyn parser::PrecioCompraVenta<T> + Send>, ClientError> {
+        self.guarded(cotizacion, &HeaderMap::new(), true).await
+    }
 
-        let domain = dolar::DOLAR_HOY_DOMAIN;
-        let uri = cotizacion.endpoint();
-        let content = format!("GET {} HTTP/1.0\r\nHost: {}\r\n\r\n", uri, domain);
+    /// Fetches the prices of all the given currencies concurrently
+    /// Results are returned in the same order as the given currencies
+    pub async fn fetch_cotizaciones<T: PrecioType>(
+        &self,
+        cotizaciones: &[dolar::Cotizacion],
+    ) -> Vec<Result<Box<dyn parser::PrecioCompraVenta<T>>, ClientError>> {
+        join_all(
+            cotizaciones
+                .iter()
+                .map(|cotizacion| self.fetch_cotizacion(cotizacion.clone())),
+        )
+        .await
+    }
+
+    /// Fetches the prices of the given currency without waiting for the rate limiter
+    /// Returns ClientError::RateLimited if the request can't be made right away
+    pub async fn try_fetch_cotizacion<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+    ) -> Result<Box<dyn parser::PrecioCompraVenta<T>>, ClientError> {
+        Ok(self.guarded(cotizacion, &HeaderMap::new(), false).await?)
+    }
+
+    /// Lists the quotes linked from the dolarhoy.com homepage, along with their titles
+    /// Resources not known by this crate are returned as Cotizacion::Other, which can be fetched as usual
+    pub async fn discover_cotizaciones(&self) -> Result<Vec<parser::Widget>, ClientError> {
+        self.fetch_homepage(|content| Ok(parser::widgets(content)))
+            .await
+    }
 
-        let root_store = rustls::RootCertStore {
-            roots: webpki_roots::TLS_SERVER_ROOTS.iter().cloned().collect(),
+    /// Fetches every quote shown on the dolarhoy.com homepage with a single request
+    /// Quotes are mapped to a Cotizacion by their link or title, see parser::Snapshot
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use dolarhoy_core::{client, dolar::Cotizacion, error};
+    ///
+    /// # async fn run() -> Result<(), error::ClientError> {
+    /// let client = client::DolayHoyClient::new();
+    /// let snapshot = client.fetch_homepage_snapshot::<f64>().await?;
+    ///
+    /// if let Some(blue) = snapshot.get(&Cotizacion::Blue) {
+    ///     println!("{}: {:?}", blue.title, blue.precio);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn fetch_homepage_snapshot<T: PrecioType>(
+        &self,
+    ) -> Result<parser::Snapshot<T>, ClientError> {
+        self.fetch_homepage(|content| Ok(parser::Snapshot::from_homepage(content)?))
+            .await
+    }
+
+    /// Fetches the whole homepage and parses it, going through the observer, the circuit breaker and the rate limiter
+    async fn fetch_homepage<R>(
+        &self,
+        parse: impl FnOnce(&str) -> Result<R, ClientError>,
+    ) -> Result<R, ClientError> {
+        let homepage = dolar::Cotizacion::Other(observer::HOMEPAGE.to_string());
+        let request = async {
+            let content = self
+                .admitted(true, self.send_hedged("/", &HeaderMap::new(), false))
+                .await?;
+            Ok((parse(&content)?, content.len()))
         };
 
-        let config = rustls::ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_no_client_auth();
-        let connector = TlsConnector::from(Arc::new(config));
-        let stream = TcpStream::connect(&addr).await?;
+        self.observed(&homepage, Box::pin(request)).await
+    }
 
-        let domain = pki_types::ServerName::try_from(domain)
-            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "invalid dnsname"))?;
+    /// Runs a request through the circuit breaker and the rate limiter
+    /// When `wait` is false, fails with ClientError::RateLimited instead of waiting
+    async fn guarded<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+        headers: &HeaderMap,
+        wait: bool,
+    ) -> Result<Box<dyn parser::PrecioCompraVenta<T> + Send>, ClientError> {
+        // Boxed, as awaiting it inside observed would otherwise double the size of the future
+        let request = Box::pin(self.throttled(cotizacion.clone(), headers, wait));
+        self.observed(&cotizacion, request).await
+    }
+
+    /// Reports a request to the observer, if any
+    /// The request returns its result along with the size of the content
+    async fn observed<R, F>(
+        &self,
+        cotizacion: &dolar::Cotizacion,
+        request: F,
+    ) -> Result<R, ClientError>
+    where
+        F: Future<Output = Result<(R, usize), ClientError>>,
+    {
+        let Some(observer) = &self.observer else {
+            return request.await.map(|(result, _)| result);
+        };
+
+        let start = Instant::now();
+        observer.on_request(cotizacion);
+
+        match request.await {
+            Ok((result, bytes)) => {
+                observer.on_success(cotizacion, start.elapsed(), bytes);
+                Ok(result)
+            }
+            Err(e) => {
+                observer.on_error(cotizacion, &e, start.elapsed());
+                Err(e)
+            }
+        }
+    }
+
+    /// Checks the circuit breaker and waits for the rate limiter before executing the request
+    async fn throttled<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+        headers: &HeaderMap,
+        wait: bool,
+    ) -> Result<(Box<dyn parser::PrecioCompraVenta<T> + Send>, usize), ClientError> {
+        self.admitted(wait, self.execute(cotizacion, headers)).await
+    }
+
+    /// Runs the given request once the circuit breaker and the rate limiter allow it
+    /// The outcome is reported to the circuit breaker
+    async fn admitted<R, F>(&self, wait: bool, request: F) -> Result<R, ClientError>
+    where
+        F: Future<Output = Result<R, ClientError>>,
+    {
+        let probe = match &self.breaker {
+            Some(breaker) => Some(breaker.acquire()?),
+            None => None,
+        };
+
+        let _permits = self.permit(wait).await?;
+
+        let result = request.await;
+        if let Some(probe) = probe {
+            probe.record(&result);
+        }
+
+        result
+    }
+
+    /// Waits for the limiters of every host the request goes through (dolarhoy.com and the proxy)
+    /// Fails with ClientError::RateLimited instead of waiting if `wait` isn't set
+    /// The returned permits must be held until the request is done
+    async fn permit(&self, wait: bool) -> Result<Vec<OwnedSemaphorePermit>, ClientError> {
+        let hosts = [
+            Some(dolar::DOLAR_HOY_DOMAIN),
+            self.route_proxy().map(|proxy| proxy.host()),
+        ];
+
+        let mut permits = Vec::new();
+        for host in hosts.into_iter().flatten() {
+            let permit = match self.limiters.get(&host.to_ascii_lowercase()) {
+                Some(limiter) if wait => limiter.acquire().await,
+                Some(limiter) => limiter.try_acquire()?,
+                None => None,
+            };
+            permits.extend(permit);
+        }
+
+        Ok(permits)
+    }
+
+    /// Makes the request and parses the response
+    /// Returns the parsed prices along with the size of the HTML content
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "fetch_cotizacion",
+            skip_all,
+            fields(
+                cotizacion = %cotizacion,
+                endpoint = %cotizacion.endpoint(),
+                elapsed_ms = tracing::field::Empty
+            ),
+            err
+        )
+    )]
+    async fn execute<T: PrecioType>(
+        &self,
+        cotizacion: dolar::Cotizacion,
+        headers: &HeaderMap,
+    ) -> Result<(Box<dyn parser::PrecioCompraVenta<T> + Send>, usize), ClientError> {
+        let start = Instant::now();
+        if let dolar::Cotizacion::Other(resource) = &cotizacion {
+            if !dolar::is_resource_name(resource) {
+                return Err(ClientError::InvalidResourceError(resource.clone()));
+            }
+        }
+
+        let content = self
+            .send_hedged(&cotizacion.endpoint(), headers, true)
+            .await?;
+
+        let result = parse_cotizacion(&cotizacion, &content)?;
+        record_elapsed!(start);
+        Ok((result, content.len()))
+    }
+
+    /// Same as send, starting a second attempt if the first one is too slow
+    /// The first successful response wins, the other attempt is cancelled
+    async fn send_hedged(
+        &self,
+        uri: &str,
+        headers: &HeaderMap,
+        stop_at_container: bool,
+    ) -> Result<String, ClientError> {
+        let Some(hedger) = &self.hedger else {
+            return self.send(uri, headers, stop_at_container).await;
+        };
+
+        hedger.record_request();
+
+        let first = self.send(uri, headers, stop_at_container);
+        tokio::pin!(first);
+
+        tokio::select! {
+            result = &mut first => return result,
+            _ = self.runtime.sleep(hedger.delay()) => {}
+        }
+
+        if !hedger.try_hedge() {
+            return first.await;
+        }
+
+        // The second attempt counts against the rate limiter as well, but never waits for it
+        let Ok(permits) = self.permit(false).await else {
+            return first.await;
+        };
+
+        let second = async move {
+            let _permits = permits;
+            self.send(uri, headers, stop_at_container).await
+        };
+        tokio::pin!(second);
+
+        tokio::select! {
+            result = &mut first => match result {
+                Ok(content) => Ok(content),
+                Err(_) => second.await,
+            },
+            result = &mut second => match result {
+                Ok(content) => Ok(content),
+                Err(_) => first.await,
+            },
+        }
+    }
+
+    /// Sends a GET request for the given URI and returns the HTML content
+    /// If `stop_at_container` is set, reading stops once the .container__data block is received
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "request", skip(self, headers), fields(elapsed_ms = tracing::field::Empty), err)
+    )]
+    async fn send(
+        &self,
+        uri: &str,
+        headers: &HeaderMap,
+        stop_at_container: bool,
+    ) -> Result<String, ClientError> {
+        let start = Instant::now();
+        let request = build_request(uri, dolar::DOLAR_HOY_DOMAIN, &self.headers, headers);
 
-        let mut stream = connector.connect(domain, stream).await?;
-        stream.write_all(content.as_bytes()).await?;
+        let stream = self.connect().await?;
+        let mut stream = self.handshake(stream).await?;
+        write_request(&mut stream, &request).await?;
 
-        let (mut reader, _) = split(stream);
+        let (reader, _) = split(stream);
+        let buffer = read_response(reader, self.max_response_size, stop_at_container).await?;
 
-        let mut buffer = Vec::new();
-        reader.read_to_end(&mut buffer).await?;
+        let content = parse_response(buffer)?;
+        record_elapsed!(start);
+        Ok(content)
+    }
+}
+
+impl Default for DolayHoyClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        let res = String::from_utf8(buffer).map_err(|e| InvalidResponseError(e.to_string()))?;
+/// Writes the whole request to the stream
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "write", skip_all, fields(bytes = request.len()), err)
+)]
+async fn write_request<W: AsyncWrite + Unpin>(stream: &mut W, request: &[u8]) -> io::Result<()> {
+    stream.write_all(request).await
+}
+
+/// Reads a response, failing if it goes over `limit` bytes
+/// If `stop_at_container` is set, returns as soon as the .container__data block is complete
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "read",
+        skip(reader),
+        fields(bytes = tracing::field::Empty, elapsed_ms = tracing::field::Empty),
+        err
+    )
+)]
+async fn read_response<R: AsyncRead + Unpin>(
+    mut reader: R,
+    limit: usize,
+    stop_at_container: bool,
+) -> Result<Vec<u8>, ClientError> {
+    let start = Instant::now();
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let mut body_start = None;
+    let mut scanner = parser::ContainerScanner::new();
+
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
 
-        match &res.find("\r\n\r\n") {
-            None => {
-                return Err(InvalidResponseError("invalid content".to_string()));
+        if buffer.len() + n > limit {
+            return Err(ClientError::ResponseTooLarge { limit });
+        }
+        let received = buffer.len();
+        buffer.extend_from_slice(&chunk[..n]);
+
+        if stop_at_container {
+            // Only the new bytes are scanned, along with the tail of the previous chunk
+            if body_start.is_none() {
+                let from = received.saturating_sub(3);
+                body_start = buffer[from..]
+                    .windows(4)
+                    .position(|w| w == b"\r\n\r\n")
+                    .map(|p| from + p + 4);
             }
-            Some(s) => {
-                let (_, response) = parser::http_response(&res.as_str()[..(*s)])
-                    .map_err(|e| InvalidResponseError(e.to_string()))?;
 
-                if !response.status_ok() {
-                    return Err(ResponseStatusError(response.status));
+            if let Some(start) = body_start {
+                if let Some(end) = scanner.scan(&buffer[start..]) {
+                    buffer.truncate(start + end);
+                    break;
                 }
+            }
+        }
+    }
+
+    record!("bytes", buffer.len());
+    record_elapsed!(start);
+    Ok(buffer)
+}
+
+/// Parses the status line and headers, and returns the HTML content
+/// Fails on error pages, unexpected status codes and non-HTML content types
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "parse_status", skip_all, fields(status = tracing::field::Empty), err)
+)]
+fn parse_response(buffer: Vec<u8>) -> Result<String, ClientError> {
+    use ClientError::*;
 
-                let content = &res.as_str()[(*s + 4)..];
+    let mut res = String::from_utf8(buffer).map_err(|e| InvalidResponseError(e.to_string()))?;
 
-                match cotizacion {
-                    Cotizacion::Crypto => {
-                        return Ok(Box::new(parser::CotizacionValor::from_html(content)?))
-                    }
-                    Cotizacion::Tarjeta => {
-                        return Ok(Box::new(parser::CotizacionValor::from_html(content)?))
-                    }
-                    _ => return Ok(Box::new(parser::CotizacionCompraVenta::from_html(content)?)),
+    match res.find("\r\n\r\n") {
+        None => Err(InvalidResponseError("invalid content".to_string())),
+        Some(s) => {
+            let head = &res.as_str()[..(s + 2)];
+            let (_, response) =
+                parser::http_response(head).map_err(|e| InvalidResponseError(e.to_string()))?;
+            record!("status", response.status);
+
+            let headers = head
+                .split_once("\r\n")
+                .and_then(|(_, rest)| parser::http_headers(rest).ok())
+                .map(|(_, headers)| headers)
+                .unwrap_or_default();
+
+            let content = &res.as_str()[(s + 4)..];
+
+            match parser::page_kind(content) {
+                parser::PageKind::Challenge => return Err(BlockedByChallenge),
+                parser::PageKind::Maintenance => return Err(MaintenancePage),
+                parser::PageKind::Content => {}
+            }
+
+            if !response.status_ok() {
+                return Err(ResponseStatusError(response.status));
+            }
+
+            if let Some(content_type) = parser::header_value(&headers, "content-type") {
+                if !parser::is_html_content_type(content_type) {
+                    return Err(UnexpectedContentType(content_type.to_string()));
                 }
             }
+
+            Ok(res.split_off(s + 4))
+        }
+    }
+}
+
+/// Parses the prices of the given currency from the HTML content
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "parse_html", skip(content), fields(bytes = content.len()), err)
+)]
+fn parse_cotizacion<T: PrecioType>(
+    cotizacion: &dolar::Cotizacion,
+    content: &str,
+) -> Result<Box<dyn parser::PrecioCompraVenta<T> + Send>, ClientError> {
+    match cotizacion {
+        Cotizacion::Crypto => Ok(Box::new(parser::CotizacionValor::from_html(content)?)),
+        Cotizacion::Tarjeta => Ok(Box::new(parser::CotizacionValor::from_html(content)?)),
+        // Futures are quoted with a single settlement price
+        Cotizacion::Futuro => Ok(Box::new(parser::CotizacionValor::from_html(content)?)),
+        // The shape of unknown resources isn't known, fall back to a single value
+        Cotizacion::Other(_) => match parser::CotizacionCompraVenta::from_html(content) {
+            Ok(precio) => Ok(Box::new(precio)),
+            Err(_) => Ok(Box::new(parser::CotizacionValor::from_html(content)?)),
+        },
+        _ => Ok(Box::new(parser::CotizacionCompraVenta::from_html(content)?)),
+    }
+}
+
+/// Builds a GET request, overriding the default headers with the given ones
+/// Header names and values are validated on creation, so they can't contain CRLF
+fn build_request(uri: &str, host: &str, defaults: &HeaderMap, overrides: &HeaderMap) -> Vec<u8> {
+    let mut headers = defaults.clone();
+    for name in overrides.keys() {
+        headers.remove(name);
+        for value in overrides.get_all(name) {
+            headers.append(name, value.clone());
+        }
+    }
+    headers.remove(header::HOST);
+
+    let mut request = format!("GET {} HTTP/1.0\r\nHost: {}\r\n", uri, host).into_bytes();
+    for (name, value) in headers.iter() {
+        request.extend_from_slice(name.as_str().as_bytes());
+        request.extend_from_slice(b": ");
+        request.extend_from_slice(value.as_bytes());
+        request.extend_from_slice(b"\r\n");
+    }
+    request.extend_from_slice(b"\r\n");
+
+    request
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_request_default_headers() {
+        let client = DolayHoyClient::new();
+        let request = build_request(
+            "/i/cotizaciones/dolar-blue",
+            "dolarhoy.com",
+            &client.headers,
+            &HeaderMap::new(),
+        );
+
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            format!(
+                "GET /i/cotizaciones/dolar-blue HTTP/1.0\r\nHost: dolarhoy.com\r\nuser-agent: {}\r\n\r\n",
+                DEFAULT_USER_AGENT
+            )
+        );
+    }
+
+    #[test]
+    fn test_build_request_override_headers() {
+        let client = DolayHoyClient::builder()
+            .user_agent(HeaderValue::from_static("my-service/1.0"))
+            .accept_language(HeaderValue::from_static("es-AR"))
+            .build();
+
+        let mut overrides = HeaderMap::new();
+        overrides.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US"));
+        overrides.insert(header::HOST, HeaderValue::from_static("example.com"));
+
+        let request = build_request("/", "dolarhoy.com", &client.headers, &overrides);
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET / HTTP/1.0\r\nHost: dolarhoy.com\r\nuser-agent: my-service/1.0\r\naccept-language: en-US\r\n\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_response_limit() {
+        let response = b"HTTP/1.1 200 OK\r\n\r\n<html>0123456789</html>";
+
+        let buffer = read_response(&response[..], 1024, false).await.unwrap();
+        assert_eq!(buffer, response);
+
+        let result = read_response(&response[..], 16, false).await;
+        assert!(matches!(
+            result,
+            Err(ClientError::ResponseTooLarge { limit: 16 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_response_stops_at_container() {
+        let head = "HTTP/1.1 200 OK\r\n\r\n";
+        let container = r#"<div class="container__data"><div><p>566.00</p></div></div>"#;
+        let response = format!("{}{}{}", head, container, "x".repeat(100_000));
+
+        // The trailing content would go over the limit
+        let buffer = read_response(response.as_bytes(), 50_000, true)
+            .await
+            .unwrap();
+        assert_eq!(buffer, format!("{}{}", head, container).as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_read_response_skips_style_marker() {
+        let head = "HTTP/1.1 200 OK\r\n\r\n";
+        let style = "<style>.container__data{color:red}</style>";
+        let container = r#"<div class="container__data"><div><p>566.00</p></div></div>"#;
+        let response = format!("{}{}{}{}<p>", head, style, "x".repeat(20_000), container);
+
+        let buffer = read_response(response.as_bytes(), 50_000, true)
+            .await
+            .unwrap();
+        assert_eq!(buffer, response.trim_end_matches("<p>").as_bytes());
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl ClientObserver for RecordingObserver {
+        fn on_request(&self, cotizacion: &Cotizacion) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("request {}", cotizacion));
+        }
+
+        fn on_error(&self, cotizacion: &Cotizacion, error: &ClientError, _latency: Duration) {
+            self.events
+                .lock()
+                .unwrap()
+                .push(format!("error {} {}", cotizacion, error.kind()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_observer_notified_on_error() {
+        let observer = Arc::new(RecordingObserver::default());
+
+        // Nothing is registered, so the lookup fails without touching the network
+        let client = DolayHoyClient::builder()
+            .resolver(resolver::StaticResolver::new())
+            .observer(observer.clone())
+            .build();
+
+        let result = client.fetch_cotizacion::<f64>(Cotizacion::Blue).await;
+        assert!(matches!(result, Err(ClientError::RequestError(_))));
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            vec!["request Blue".to_string(), "error Blue request".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_observer_notified_on_homepage() {
+        let observer = Arc::new(RecordingObserver::default());
+        let client = DolayHoyClient::builder()
+            .resolver(resolver::StaticResolver::new())
+            .observer(observer.clone())
+            .build();
+
+        assert!(client.discover_cotizaciones().await.is_err());
+        assert!(client.fetch_homepage_snapshot::<f64>().await.is_err());
+
+        assert_eq!(
+            *observer.events.lock().unwrap(),
+            vec![
+                "request homepage".to_string(),
+                "error homepage request".to_string(),
+                "request homepage".to_string(),
+                "error homepage request".to_string(),
+            ]
+        );
+    }
+
+    /// Counts lookups, failing each one after a delay
+    #[cfg(feature = "tokio")]
+    struct SlowResolver {
+        lookups: Arc<std::sync::atomic::AtomicUsize>,
+        delay: Duration,
+    }
+
+    #[cfg(feature = "tokio")]
+    impl Resolve for SlowResolver {
+        fn resolve(&self, host: &str, _port: u16) -> resolver::Resolving {
+            self.lookups
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let delay = self.delay;
+            let host = host.to_owned();
+
+            Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                Err(io::Error::new(io::ErrorKind::NotFound, host))
+            })
         }
     }
+
+    // Paused time only applies to tokio timers
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_hedge_respects_max_in_flight() {
+        let lookups = |max_in_flight: Option<usize>| async move {
+            let counter = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+            let mut builder = DolayHoyClient::builder()
+                .resolver(SlowResolver {
+                    lookups: counter.clone(),
+                    delay: Duration::from_millis(200),
+                })
+                .hedge(HedgePolicy::new(Duration::from_millis(10)).max_ratio(1.0));
+            if let Some(max) = max_in_flight {
+                builder = builder.max_in_flight(max);
+            }
+
+            let result = builder
+                .build()
+                .fetch_cotizacion::<f64>(Cotizacion::Blue)
+                .await;
+            assert!(matches!(result, Err(ClientError::RequestError(_))));
+            counter.load(std::sync::atomic::Ordering::SeqCst)
+        };
+
+        assert_eq!(lookups(None).await, 2);
+        // The only slot is taken by the first attempt, so no hedge is sent
+        assert_eq!(lookups(Some(1)).await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_host_limits_apply_to_proxy() {
+        let client = DolayHoyClient::builder()
+            .resolver(resolver::StaticResolver::new())
+            .proxy(Proxy::http("Proxy.Local", 3128))
+            .host_limits(
+                "proxy.local",
+                HostLimits {
+                    rate: Some(RateLimit::per_minute(1)),
+                    max_in_flight: None,
+                },
+            )
+            .build();
+
+        let result = client.try_fetch_cotizacion::<f64>(Cotizacion::Blue).await;
+        assert!(matches!(result, Err(ClientError::RequestError(_))));
+
+        let result = client.try_fetch_cotizacion::<f64>(Cotizacion::Blue).await;
+        assert!(matches!(result, Err(ClientError::RateLimited)));
+    }
+
+    #[tokio::test]
+    async fn test_socks5_local_dns_uses_resolver() {
+        let fetch = |url: &str| {
+            let client = DolayHoyClient::builder()
+                .resolver(
+                    resolver::StaticResolver::new()
+                        .host("proxy.local", [crate::testing::refused_addr()]),
+                )
+                .proxy(Proxy::parse(url).unwrap())
+                .build();
+            async move {
+                match client.try_fetch_cotizacion::<f64>(Cotizacion::Blue).await {
+                    Err(ClientError::RequestError(e)) => e.kind(),
+                    _ => panic!("expected a request error"),
+                }
+            }
+        };
+
+        // dolarhoy.com isn't registered, so only the local lookup fails to resolve
+        assert_eq!(fetch("socks5://proxy.local").await, io::ErrorKind::NotFound);
+        assert_eq!(
+            fetch("socks5h://proxy.local").await,
+            io::ErrorKind::ConnectionRefused
+        );
+    }
+
+    #[test]
+    fn test_header_rejects_crlf() {
+        assert!(HeaderValue::from_str("value\r\nX-Injected: 1").is_err());
+        assert!(HeaderName::from_bytes(b"X-Bad\r\n").is_err());
+
+        let err: ClientError = HeaderValue::from_str("a\nb").unwrap_err().into();
+        assert!(matches!(err, ClientError::InvalidHeaderError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_other_rejects_invalid_resource() {
+        let client = DolayHoyClient::builder()
+            .resolver(resolver::StaticResolver::new())
+            .build();
+
+        let other = Cotizacion::Other("dolar-blue HTTP/1.1\r\nX-Injected: 1".to_string());
+        let result = client.fetch_cotizacion::<f64>(other).await;
+        assert!(matches!(result, Err(ClientError::InvalidResourceError(_))));
+    }
+
+    #[test]
+    fn test_parse_other_cotizacion() {
+        let other = Cotizacion::Other("dolar-qatar".to_string());
+
+        let compra_venta = r#"
+        <div class="container__data">
+            <h2 class="data__titulo">Dólar Qatar</h2>
+            <div class="data__valores">
+                <p>1100.00<span>Compra</span></p>
+                <p>1150.00<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+        let precio = parse_cotizacion::<f64>(&other, compra_venta).unwrap();
+        assert_eq!(precio.precio_compra_venta(), (1100.0, Some(1150.0)));
+
+        let valor = r#"
+        <div class="container__data">
+            <h2 class="data__titulo">Dólar Qatar</h2>
+            <div class="data__valores">
+                <p>1150.00<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+        let precio = parse_cotizacion::<f64>(&other, valor).unwrap();
+        assert_eq!(precio.precio_compra_venta(), (1150.0, None));
+    }
 }
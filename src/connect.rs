@@ -0,0 +1,101 @@
+use crate::runtime::{Io, Runtime};
+use futures_util::future::FutureExt;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, Instant};
+
+/// Default delay between connection attempts, as recommended by RFC 8305
+pub const DEFAULT_HAPPY_EYEBALLS_DELAY: Duration = Duration::from_millis(250);
+
+/// Connects to the first reachable address, happy-eyeballs style
+/// A new attempt is started every `delay`, or as soon as the previous one fails
+/// Addresses are expected to be already sorted (see resolver::interleave)
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "connect",
+        skip_all,
+        fields(candidates = addrs.len(), peer = tracing::field::Empty, elapsed_ms = tracing::field::Empty),
+        err
+    )
+)]
+pub(crate) async fn happy_eyeballs(
+    runtime: &dyn Runtime,
+    addrs: Vec<SocketAddr>,
+    delay: Duration,
+) -> io::Result<Box<dyn Io>> {
+    let attempt = |addr: SocketAddr| runtime.connect(addr).map(move |result| (addr, result));
+
+    let mut pending = addrs.into_iter();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+    let start = Instant::now();
+
+    loop {
+        if attempts.is_empty() {
+            match pending.next() {
+                Some(addr) => attempts.push(attempt(addr)),
+                None => break,
+            }
+        }
+
+        tokio::select! {
+            result = attempts.next() => match result {
+                Some((addr, Ok(stream))) => {
+                    record!("peer", addr.to_string().as_str());
+                    record_elapsed!(start);
+                    return Ok(stream);
+                }
+                Some((_, Err(e))) => {
+                    last_error = Some(e);
+                    if let Some(addr) = pending.next() {
+                        attempts.push(attempt(addr));
+                    }
+                }
+                None => {}
+            },
+            _ = runtime.sleep(delay), if !pending.as_slice().is_empty() => {
+                if let Some(addr) = pending.next() {
+                    attempts.push(attempt(addr));
+                }
+            }
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no addresses to connect to")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::default_runtime;
+    use crate::testing::refused_addr;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_happy_eyeballs_fallback() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap();
+        let unreachable = refused_addr();
+
+        let stream = happy_eyeballs(
+            &*default_runtime(),
+            vec![unreachable, reachable],
+            DEFAULT_HAPPY_EYEBALLS_DELAY,
+        )
+        .await;
+        assert!(stream.is_ok());
+
+        let (accepted, _) = listener.accept().await.unwrap();
+        assert_eq!(accepted.local_addr().unwrap(), reachable);
+    }
+
+    #[tokio::test]
+    async fn test_happy_eyeballs_no_addresses() {
+        let result =
+            happy_eyeballs(&*default_runtime(), vec![], DEFAULT_HAPPY_EYEBALLS_DELAY).await;
+        assert!(matches!(result, Err(e) if e.kind() == io::ErrorKind::NotFound));
+    }
+}
@@ -0,0 +1,624 @@
+use crate::dolar::{Cotizacion, Moneda, Par};
+use crate::parser::PrecioCompraVenta;
+use crate::precio::Centavos;
+use std::error::Error;
+use std::fmt;
+
+#[cfg(feature = "client")]
+use crate::{
+    client::{DolayHoyClient, PrecioType},
+    error::ClientError,
+};
+
+/// The side of a quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    /// The price at which the foreign currency is bought from you
+    Compra,
+    /// The price at which the foreign currency is sold to you
+    Venta,
+}
+
+/// What you do with the foreign currency (Ex: USD), which decides the side of the quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Buying the foreign currency, the venta price is used
+    Buy,
+    /// Selling the foreign currency, the compra price is used
+    Sell,
+}
+
+impl Direction {
+    /// Returns the side of the quote used for this direction
+    pub fn side(&self) -> Side {
+        match self {
+            Self::Buy => Side::Venta,
+            Self::Sell => Side::Compra,
+        }
+    }
+}
+
+/// How ties and discarded digits are handled when rounding
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Rounds to the nearest value, ties away from zero
+    #[default]
+    HalfUp,
+    /// Rounds to the nearest value, ties to the even neighbour
+    HalfEven,
+    /// Rounds towards zero
+    Down,
+    /// Rounds away from zero
+    Up,
+}
+
+/// The rounding applied to a converted amount
+/// Defaults to 2 decimals, rounding half up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rounding {
+    decimals: u32,
+    mode: RoundingMode,
+}
+
+impl Rounding {
+    /// Returns a Rounding to the given amount of decimals
+    pub fn new(decimals: u32, mode: RoundingMode) -> Self {
+        Rounding { decimals, mode }
+    }
+
+    /// Returns the amount of decimals kept
+    pub fn decimals(&self) -> u32 {
+        self.decimals
+    }
+
+    /// Returns the rounding mode
+    pub fn mode(&self) -> RoundingMode {
+        self.mode
+    }
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Rounding::new(2, RoundingMode::HalfUp)
+    }
+}
+
+/// An enum describing all possible errors during a conversion
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConversionError {
+    /// The quote isn't priced in a supported currency pair
+    UnsupportedCotizacion(Cotizacion),
+    /// The amount's currency isn't part of the quote
+    UnsupportedMoneda(Moneda),
+    /// The quote price is zero
+    ZeroPrice,
+    /// The result doesn't fit in the amount type
+    Overflow,
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedCotizacion(cotizacion) => {
+                write!(f, "conversions are not supported for {}", cotizacion)
+            }
+            Self::UnsupportedMoneda(moneda) => write!(f, "{} is not part of the quote", moneda),
+            Self::ZeroPrice => f.write_str("the quote price is zero"),
+            Self::Overflow => f.write_str("the converted amount overflows"),
+        }
+    }
+}
+
+impl Error for ConversionError {}
+
+/// A trait for amount types that can be multiplied and divided by a price
+/// Each operation rounds the exact result once, using the given Rounding
+pub trait Convertible: Copy {
+    /// Returns the given amount of whole units
+    fn from_units(units: i64) -> Result<Self, ConversionError>;
+
+    /// Returns the amount of whole units, truncated towards zero
+    fn to_units(self) -> Result<i64, ConversionError>;
+
+    /// Returns `self + precio`
+    fn add_precio(self, precio: Self) -> Result<Self, ConversionError>;
+
+    /// Returns `self - precio`
+    fn sub_precio(self, precio: Self) -> Result<Self, ConversionError>;
+
+    /// Returns `self * precio`, rounded
+    fn mul_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError>;
+
+    /// Returns `self / precio`, rounded
+    fn div_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError>;
+}
+
+macro_rules! impl_convertible_float {
+    ($t:ty) => {
+        impl Convertible for $t {
+            fn from_units(units: i64) -> Result<Self, ConversionError> {
+                Ok(units as $t)
+            }
+
+            fn to_units(self) -> Result<i64, ConversionError> {
+                let units = (self as f64).trunc();
+                if units.is_finite() && units >= i64::MIN as f64 && units < i64::MAX as f64 {
+                    Ok(units as i64)
+                } else {
+                    Err(ConversionError::Overflow)
+                }
+            }
+
+            fn add_precio(self, precio: Self) -> Result<Self, ConversionError> {
+                finite(self + precio)
+            }
+
+            fn sub_precio(self, precio: Self) -> Result<Self, ConversionError> {
+                finite(self - precio)
+            }
+
+            fn mul_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+                round_float((self as f64) * (precio as f64), rounding).map(|v| v as $t)
+            }
+
+            fn div_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+                if precio == 0.0 {
+                    return Err(ConversionError::ZeroPrice);
+                }
+                round_float((self as f64) / (precio as f64), rounding).map(|v| v as $t)
+            }
+        }
+    };
+}
+
+impl_convertible_float!(f32);
+impl_convertible_float!(f64);
+
+/// Fails with ConversionError::Overflow if the value isn't finite
+fn finite<F: Into<f64> + Copy>(value: F) -> Result<F, ConversionError> {
+    if value.into().is_finite() {
+        Ok(value)
+    } else {
+        Err(ConversionError::Overflow)
+    }
+}
+
+/// Rounds a float to the given decimals
+fn round_float(value: f64, rounding: Rounding) -> Result<f64, ConversionError> {
+    if !value.is_finite() {
+        return Err(ConversionError::Overflow);
+    }
+
+    let scale = 10f64.powi(rounding.decimals.min(i32::MAX as u32) as i32);
+    let scaled = value * scale;
+    if !scaled.is_finite() {
+        // More decimals than a float can hold, there's nothing to round
+        return Ok(value);
+    }
+
+    let rounded = match rounding.mode {
+        RoundingMode::HalfUp => scaled.round(),
+        RoundingMode::HalfEven => scaled.round_ties_even(),
+        RoundingMode::Down => scaled.trunc(),
+        RoundingMode::Up if scaled.fract() == 0.0 => scaled,
+        RoundingMode::Up => scaled.trunc() + scaled.signum(),
+    };
+
+    Ok(rounded / scale)
+}
+
+impl Convertible for Centavos {
+    fn from_units(units: i64) -> Result<Self, ConversionError> {
+        Centavos::from_pesos(units).ok_or(ConversionError::Overflow)
+    }
+
+    fn to_units(self) -> Result<i64, ConversionError> {
+        Ok(self.pesos())
+    }
+
+    fn add_precio(self, precio: Self) -> Result<Self, ConversionError> {
+        self.0
+            .checked_add(precio.0)
+            .map(Centavos)
+            .ok_or(ConversionError::Overflow)
+    }
+
+    fn sub_precio(self, precio: Self) -> Result<Self, ConversionError> {
+        self.0
+            .checked_sub(precio.0)
+            .map(Centavos)
+            .ok_or(ConversionError::Overflow)
+    }
+
+    fn mul_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+        // Both values are in cents, so the product is scaled twice
+        let value = self.0 as i128 * precio.0 as i128;
+        round_centavos(value, Centavos::SCALE as i128, rounding)
+    }
+
+    fn div_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+        if precio.0 == 0 {
+            return Err(ConversionError::ZeroPrice);
+        }
+
+        let value = self.0 as i128 * Centavos::SCALE as i128;
+        round_centavos(value, precio.0 as i128, rounding)
+    }
+}
+
+/// Returns `numerator / denominator` in cents, rounded to the given decimals
+/// Centavos can't hold more than 2 decimals, so larger values are clamped
+fn round_centavos(
+    numerator: i128,
+    denominator: i128,
+    rounding: Rounding,
+) -> Result<Centavos, ConversionError> {
+    let unit = 10i128.pow(2 - rounding.decimals.min(2));
+    let value = round_div(numerator, denominator * unit, rounding.mode) * unit;
+
+    i64::try_from(value)
+        .map(Centavos)
+        .map_err(|_| ConversionError::Overflow)
+}
+
+/// Divides two integers, rounding the quotient with the given mode
+fn round_div(numerator: i128, denominator: i128, mode: RoundingMode) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return quotient;
+    }
+
+    let away = quotient
+        + if (numerator < 0) != (denominator < 0) {
+            -1
+        } else {
+            1
+        };
+
+    // Compares the remainder against half the divisor
+    let twice = 2 * remainder.abs();
+    let divisor = denominator.abs();
+
+    match mode {
+        RoundingMode::Down => quotient,
+        RoundingMode::Up => away,
+        _ if twice > divisor => away,
+        _ if twice < divisor => quotient,
+        RoundingMode::HalfUp => away,
+        RoundingMode::HalfEven if quotient % 2 == 0 => quotient,
+        RoundingMode::HalfEven => away,
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Convertible for crate::precio::Decimal {
+    fn from_units(units: i64) -> Result<Self, ConversionError> {
+        Ok(Self::from(units))
+    }
+
+    fn to_units(self) -> Result<i64, ConversionError> {
+        use rust_decimal::prelude::ToPrimitive;
+
+        self.trunc().to_i64().ok_or(ConversionError::Overflow)
+    }
+
+    fn add_precio(self, precio: Self) -> Result<Self, ConversionError> {
+        self.checked_add(precio).ok_or(ConversionError::Overflow)
+    }
+
+    fn sub_precio(self, precio: Self) -> Result<Self, ConversionError> {
+        self.checked_sub(precio).ok_or(ConversionError::Overflow)
+    }
+
+    fn mul_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+        self.checked_mul(precio)
+            .map(|value| round_decimal(value, rounding))
+            .ok_or(ConversionError::Overflow)
+    }
+
+    fn div_precio(self, precio: Self, rounding: Rounding) -> Result<Self, ConversionError> {
+        if precio.is_zero() {
+            return Err(ConversionError::ZeroPrice);
+        }
+
+        self.checked_div(precio)
+            .map(|value| round_decimal(value, rounding))
+            .ok_or(ConversionError::Overflow)
+    }
+}
+
+#[cfg(feature = "decimal")]
+fn round_decimal(value: crate::precio::Decimal, rounding: Rounding) -> crate::precio::Decimal {
+    use rust_decimal::RoundingStrategy;
+
+    let strategy = match rounding.mode {
+        RoundingMode::HalfUp => RoundingStrategy::MidpointAwayFromZero,
+        RoundingMode::HalfEven => RoundingStrategy::MidpointNearestEven,
+        RoundingMode::Down => RoundingStrategy::ToZero,
+        RoundingMode::Up => RoundingStrategy::AwayFromZero,
+    };
+
+    value.round_dp_with_strategy(rounding.decimals, strategy)
+}
+
+/// Converts an amount of money using a quote
+///
+/// # Example
+///
+/// ```
+/// use dolarhoy_core::conversion::{Conversion, Direction};
+/// use dolarhoy_core::dolar::{Cotizacion, Moneda};
+/// use dolarhoy_core::precio::Centavos;
+///
+/// // How many pesos is US$ 250 at blue?
+/// let conversion = Conversion::new(Centavos(25000), Moneda::USD, Direction::Sell);
+/// let pesos = conversion
+///     .convert(&Cotizacion::Blue, (Centavos(56600), Some(Centavos(57100))))
+///     .unwrap();
+/// assert_eq!(pesos, Centavos(14150000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conversion<T> {
+    monto: T,
+    moneda: Moneda,
+    direction: Direction,
+    rounding: Rounding,
+}
+
+impl<T: Convertible> Conversion<T> {
+    /// Returns a Conversion of `monto`, expressed in `moneda`
+    /// The result is expressed in the other currency of the quote
+    pub fn new(monto: T, moneda: Moneda, direction: Direction) -> Self {
+        Conversion {
+            monto,
+            moneda,
+            direction,
+            rounding: Rounding::default(),
+        }
+    }
+
+    /// Sets the rounding applied to the result
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Returns the side of the quote used by this conversion
+    pub fn side(&self) -> Side {
+        self.direction.side()
+    }
+
+    /// Converts the amount using the given buy/sell prices of a currency
+    /// Quotes with a single value (Ex: CotizacionValor) use it for both sides
+    pub fn convert(
+        &self,
+        cotizacion: &Cotizacion,
+        (compra, venta): (T, Option<T>),
+    ) -> Result<T, ConversionError> {
+        let Par { base, quote } = cotizacion.par();
+
+        let precio = match self.side() {
+            Side::Compra => compra,
+            Side::Venta => venta.unwrap_or(compra),
+        };
+
+        if self.moneda == base {
+            self.monto.mul_precio(precio, self.rounding)
+        } else if self.moneda == quote {
+            self.monto.div_precio(precio, self.rounding)
+        } else {
+            Err(ConversionError::UnsupportedMoneda(self.moneda))
+        }
+    }
+
+    /// Converts the amount using a parsed quote
+    pub fn convert_precio(
+        &self,
+        cotizacion: &Cotizacion,
+        precio: &dyn PrecioCompraVenta<T>,
+    ) -> Result<T, ConversionError> {
+        self.convert(cotizacion, precio.precio_compra_venta())
+    }
+
+    /// Fetches the prices of the given currency and converts the amount
+    #[cfg(feature = "client")]
+    pub async fn fetch(
+        &self,
+        client: &DolayHoyClient,
+        cotizacion: Cotizacion,
+    ) -> Result<T, ClientError>
+    where
+        T: PrecioType,
+    {
+        let precio = client.fetch_cotizacion::<T>(cotizacion.clone()).await?;
+        Ok(self.convert_precio(&cotizacion, precio.as_ref())?)
+    }
+}
+
+/// The rate of a currency pair: one `par.base` unit costs `valor` units of `par.quote`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tasa<T> {
+    pub par: Par,
+    pub valor: T,
+}
+
+impl<T: Convertible> Tasa<T> {
+    /// Returns a Tasa instance
+    pub fn new(par: Par, valor: T) -> Self {
+        Tasa { par, valor }
+    }
+
+    /// Returns the rate of a quote for the given side
+    /// Quotes with a single value (Ex: CotizacionValor) use it for both sides
+    pub fn from_cotizacion(
+        cotizacion: &Cotizacion,
+        (compra, venta): (T, Option<T>),
+        side: Side,
+    ) -> Self {
+        let valor = match side {
+            Side::Compra => compra,
+            Side::Venta => venta.unwrap_or(compra),
+        };
+
+        Tasa::new(cotizacion.par(), valor)
+    }
+
+    /// Returns the rate of the inverse pair (Ex: ARS/USD from USD/ARS), rounded
+    /// Inverse rates are usually small, so Centavos may lose most of their digits
+    pub fn invert(&self, rounding: Rounding) -> Result<Self, ConversionError> {
+        let valor = T::from_units(1)?.div_precio(self.valor, rounding)?;
+        Ok(Tasa::new(self.par.invert(), valor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLUE: (Centavos, Option<Centavos>) = (Centavos(56610), Some(Centavos(57190)));
+
+    #[test]
+    fn test_convert_uses_explicit_side() {
+        // Selling US$ 250 at the compra price
+        let sell = Conversion::new(Centavos(25000), Moneda::USD, Direction::Sell);
+        assert_eq!(sell.side(), Side::Compra);
+        assert_eq!(
+            sell.convert(&Cotizacion::Blue, BLUE),
+            Ok(Centavos(14152500))
+        );
+
+        // Buying US$ 250 at the venta price
+        let buy = Conversion::new(Centavos(25000), Moneda::USD, Direction::Buy);
+        assert_eq!(buy.side(), Side::Venta);
+        assert_eq!(buy.convert(&Cotizacion::Blue, BLUE), Ok(Centavos(14297500)));
+
+        // Dollars bought with $ 100.000, 174.8631... rounds to 174.86
+        let buy = Conversion::new(Centavos(10000000), Moneda::ARS, Direction::Buy);
+        assert_eq!(buy.convert(&Cotizacion::Blue, BLUE), Ok(Centavos(17486)));
+    }
+
+    #[test]
+    fn test_convert_single_value() {
+        let tarjeta = (Centavos(91504), None);
+
+        let buy = Conversion::new(Centavos(1000), Moneda::USD, Direction::Buy);
+        assert_eq!(
+            buy.convert(&Cotizacion::Tarjeta, tarjeta),
+            Ok(Centavos(915040))
+        );
+
+        let sell = Conversion::new(Centavos(1000), Moneda::USD, Direction::Sell);
+        assert_eq!(
+            sell.convert(&Cotizacion::Tarjeta, tarjeta),
+            Ok(Centavos(915040))
+        );
+
+        // Crypto is priced in BTC/USD
+        let sell = Conversion::new(0.5f64, Moneda::BTC, Direction::Sell);
+        assert_eq!(
+            sell.convert(&Cotizacion::Crypto, (30000.0, None)),
+            Ok(15000.0)
+        );
+
+        let buy = Conversion::new(100.0f64, Moneda::USD, Direction::Buy)
+            .rounding(Rounding::new(Moneda::BTC.minor_units(), RoundingMode::Down));
+        assert_eq!(
+            buy.convert(&Cotizacion::Crypto, (30000.0, None)),
+            Ok(0.00333333)
+        );
+    }
+
+    #[test]
+    fn test_convert_errors() {
+        let conversion = Conversion::new(1.0f64, Moneda::ARS, Direction::Sell);
+        assert_eq!(
+            conversion.convert(&Cotizacion::Crypto, (30000.0, None)),
+            Err(ConversionError::UnsupportedMoneda(Moneda::ARS))
+        );
+
+        let conversion = Conversion::new(Centavos(100), Moneda::ARS, Direction::Buy);
+        assert_eq!(
+            conversion.convert(&Cotizacion::Blue, (Centavos(0), Some(Centavos(0)))),
+            Err(ConversionError::ZeroPrice)
+        );
+
+        let conversion = Conversion::new(Centavos(i64::MAX), Moneda::USD, Direction::Sell);
+        assert_eq!(
+            conversion.convert(&Cotizacion::Blue, BLUE),
+            Err(ConversionError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_invert_tasa() {
+        let tasa = Tasa::from_cotizacion(&Cotizacion::Blue, (1000.0f64, Some(1250.0)), Side::Venta);
+        assert_eq!(tasa.par.to_string(), "USD/ARS");
+
+        let inversa = tasa.invert(Rounding::new(4, RoundingMode::HalfUp)).unwrap();
+        assert_eq!(
+            inversa,
+            Tasa::new(Par::new(Moneda::ARS, Moneda::USD), 0.0008)
+        );
+
+        assert_eq!(
+            Tasa::new(Cotizacion::Crypto.par(), Centavos(0)).invert(Rounding::default()),
+            Err(ConversionError::ZeroPrice)
+        );
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        // 1 / 8 = 0.125 is a tie at 2 decimals
+        let ars = (Centavos(800), Some(Centavos(800)));
+        let round = |mode| {
+            Conversion::new(Centavos(100), Moneda::ARS, Direction::Buy)
+                .rounding(Rounding::new(2, mode))
+                .convert(&Cotizacion::Blue, ars)
+                .unwrap()
+        };
+
+        assert_eq!(round(RoundingMode::HalfUp), Centavos(13));
+        assert_eq!(round(RoundingMode::HalfEven), Centavos(12));
+        assert_eq!(round(RoundingMode::Down), Centavos(12));
+        assert_eq!(round(RoundingMode::Up), Centavos(13));
+
+        // 250.50 * 566.10 = 141808.05
+        let conversion = Conversion::new(Centavos(25050), Moneda::USD, Direction::Sell)
+            .rounding(Rounding::new(0, RoundingMode::Down));
+        assert_eq!(
+            conversion.convert(&Cotizacion::Blue, BLUE),
+            Ok(Centavos(14180800))
+        );
+
+        let conversion = Conversion::new(0.125f64, Moneda::USD, Direction::Sell)
+            .rounding(Rounding::new(2, RoundingMode::HalfEven));
+        assert_eq!(
+            conversion.convert(&Cotizacion::Blue, (1.0, Some(1.0))),
+            Ok(0.12)
+        );
+    }
+
+    #[test]
+    fn test_to_units() {
+        assert_eq!(Centavos(56610).to_units(), Ok(566));
+        assert_eq!(Centavos(-56610).to_units(), Ok(-566));
+        assert_eq!(566.99f64.to_units(), Ok(566));
+        assert_eq!(f64::NAN.to_units(), Err(ConversionError::Overflow));
+        assert_eq!(1e20f32.to_units(), Err(ConversionError::Overflow));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_convert_decimal() {
+        use crate::precio::Decimal;
+
+        let blue = (Decimal::new(56610, 2), Some(Decimal::new(57190, 2)));
+        let conversion = Conversion::new(Decimal::new(10000000, 2), Moneda::ARS, Direction::Buy);
+        assert_eq!(
+            conversion.convert(&Cotizacion::Blue, blue),
+            Ok(Decimal::new(17486, 2))
+        );
+        assert_eq!(Decimal::new(-56699, 2).to_units(), Ok(-566));
+    }
+}
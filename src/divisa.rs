@@ -0,0 +1,280 @@
+use crate::conversion::{ConversionError, Convertible, Rounding, Side};
+use crate::dolar::{Cotizacion, Moneda, Par};
+use crate::parser::{CompraVenta, PrecioCompraVenta};
+use std::fmt;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+#[cfg(feature = "client")]
+use crate::{
+    client::{DolayHoyClient, PrecioType},
+    error::ClientError,
+};
+
+/// A trait for the currency markers used to tag prices
+pub trait Divisa:
+    fmt::Debug + Default + Clone + Copy + PartialEq + Eq + Hash + Send + Sync
+{
+    /// The currency this marker stands for
+    const MONEDA: Moneda;
+}
+
+/// Declares a currency marker for a Moneda variant
+macro_rules! divisa {
+    ($(#[$doc:meta])* $name:ident => $moneda:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name;
+
+        impl Divisa for $name {
+            const MONEDA: Moneda = Moneda::$moneda;
+        }
+    };
+}
+
+divisa! {
+    /// Marks a price expressed in Argentine Pesos
+    Ars => ARS
+}
+
+divisa! {
+    /// Marks a price expressed in US Dollars
+    Usd => USD
+}
+
+divisa! {
+    /// Marks a price expressed in Bitcoin
+    Btc => BTC
+}
+
+divisa! {
+    /// Marks a price expressed in Euros
+    Eur => EUR
+}
+
+divisa! {
+    /// Marks a price expressed in Brazilian Reales
+    Brl => BRL
+}
+
+divisa! {
+    /// Marks a price expressed in Uruguayan Pesos
+    Uyu => UYU
+}
+
+divisa! {
+    /// Marks a price expressed in Chilean Pesos
+    Clp => CLP
+}
+
+/// A price tagged with the currency it is expressed in
+/// Prices can only be compared when they share a currency:
+///
+/// ```compile_fail
+/// use dolarhoy_core::divisa::{Ars, Precio, Usd};
+///
+/// let pesos = Precio::<f64, Ars>::new(1000.0);
+/// let dolares = Precio::<f64, Usd>::new(1000.0);
+/// assert!(pesos == dolares);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Precio<T, D> {
+    valor: T,
+    divisa: PhantomData<D>,
+}
+
+impl<T: Copy, D: Divisa> Precio<T, D> {
+    /// Returns a price expressed in D
+    pub fn new(valor: T) -> Self {
+        Precio {
+            valor,
+            divisa: PhantomData,
+        }
+    }
+
+    /// Returns the untagged value
+    pub fn valor(&self) -> T {
+        self.valor
+    }
+
+    /// Returns the currency of the price
+    pub fn moneda(&self) -> Moneda {
+        D::MONEDA
+    }
+}
+
+/// The prices of a quote, tagged with the currency they are expressed in
+/// Most quotes are expressed in ARS, while Crypto is expressed in USD
+/// Every Moneda has a marker, but quotes are only expressed in their quote currency (Ex: Euro is EUR/ARS, so Cotizado<T, Ars>)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cotizado<T, D> {
+    cotizacion: Cotizacion,
+    compra: Precio<T, D>,
+    venta: Option<Precio<T, D>>,
+}
+
+impl<T: Copy, D: Divisa> Cotizado<T, D> {
+    /// Tags the prices of a quote
+    /// Fails with ConversionError::UnsupportedMoneda if the quote isn't expressed in D
+    pub fn new(
+        cotizacion: Cotizacion,
+        (compra, venta): CompraVenta<T>,
+    ) -> Result<Self, ConversionError> {
+        if cotizacion.moneda() != D::MONEDA {
+            return Err(ConversionError::UnsupportedMoneda(cotizacion.moneda()));
+        }
+
+        Ok(Cotizado {
+            cotizacion,
+            compra: Precio::new(compra),
+            venta: venta.map(Precio::new),
+        })
+    }
+
+    /// Tags the prices of a parsed quote
+    pub fn from_precio(
+        cotizacion: Cotizacion,
+        precio: &dyn PrecioCompraVenta<T>,
+    ) -> Result<Self, ConversionError> {
+        Self::new(cotizacion, precio.precio_compra_venta())
+    }
+
+    /// Fetches the prices of the given currency type, which must be expressed in D
+    #[cfg(feature = "client")]
+    pub async fn fetch(client: &DolayHoyClient, cotizacion: Cotizacion) -> Result<Self, ClientError>
+    where
+        T: PrecioType,
+    {
+        let precio = client.fetch_cotizacion::<T>(cotizacion.clone()).await?;
+        Ok(Self::from_precio(cotizacion, precio.as_ref())?)
+    }
+
+    /// Returns the quoted currency type
+    pub fn cotizacion(&self) -> &Cotizacion {
+        &self.cotizacion
+    }
+
+    /// Returns the compra price
+    pub fn compra(&self) -> Precio<T, D> {
+        self.compra
+    }
+
+    /// Returns the venta price, if the quote has one
+    pub fn venta(&self) -> Option<Precio<T, D>> {
+        self.venta
+    }
+
+    /// Returns the price for the given side
+    /// Quotes with a single value (Ex: Crypto) use it for both sides
+    pub fn precio(&self, side: Side) -> Precio<T, D> {
+        match side {
+            Side::Compra => self.compra,
+            Side::Venta => self.venta.unwrap_or(self.compra),
+        }
+    }
+}
+
+impl<T: Convertible> Cotizado<T, Usd> {
+    /// Returns the implied ARS price, using a dollar quote (Ex: Blue or Bolsa) as the rate
+    /// The same side is read from both quotes
+    pub fn to_ars(
+        &self,
+        dolar: &Cotizado<T, Ars>,
+        side: Side,
+        rounding: Rounding,
+    ) -> Result<Convertido<T, Usd, Ars>, ConversionError> {
+        if dolar.cotizacion().par() != Par::new(Moneda::USD, Moneda::ARS) {
+            return Err(ConversionError::UnsupportedCotizacion(
+                dolar.cotizacion().clone(),
+            ));
+        }
+
+        let origen = self.precio(side);
+        let tasa = dolar.precio(side);
+        let valor = origen.valor().mul_precio(tasa.valor(), rounding)?;
+
+        Ok(Convertido {
+            origen,
+            cotizacion: dolar.cotizacion().clone(),
+            tasa,
+            valor: Precio::new(valor),
+        })
+    }
+}
+
+/// A price converted from currency F to currency D, along with the rate used
+#[derive(Debug, Clone, PartialEq)]
+pub struct Convertido<T, F, D> {
+    /// The original price
+    pub origen: Precio<T, F>,
+    /// The currency type used as the rate
+    pub cotizacion: Cotizacion,
+    /// The price of one unit of F, expressed in D
+    pub tasa: Precio<T, D>,
+    /// The converted price
+    pub valor: Precio<T, D>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precio::Centavos;
+
+    #[test]
+    fn test_cotizado_checks_moneda() {
+        assert!(Cotizado::<Centavos, Usd>::new(Cotizacion::Crypto, (Centavos(100), None)).is_ok());
+        assert!(Cotizado::<Centavos, Ars>::new(Cotizacion::Blue, (Centavos(100), None)).is_ok());
+
+        assert_eq!(
+            Cotizado::<Centavos, Ars>::new(Cotizacion::Crypto, (Centavos(100), None)),
+            Err(ConversionError::UnsupportedMoneda(Moneda::USD))
+        );
+        assert_eq!(
+            Cotizado::<Centavos, Usd>::new(Cotizacion::Bolsa, (Centavos(100), None)),
+            Err(ConversionError::UnsupportedMoneda(Moneda::ARS))
+        );
+    }
+
+    #[test]
+    fn test_markers() {
+        assert_eq!(Precio::<Centavos, Ars>::default().moneda(), Moneda::ARS);
+        assert_eq!(Precio::<Centavos, Usd>::default().moneda(), Moneda::USD);
+        assert_eq!(Precio::<Centavos, Btc>::default().moneda(), Moneda::BTC);
+        assert_eq!(Precio::<Centavos, Eur>::default().moneda(), Moneda::EUR);
+        assert_eq!(Precio::<Centavos, Brl>::default().moneda(), Moneda::BRL);
+        assert_eq!(Precio::<Centavos, Uyu>::default().moneda(), Moneda::UYU);
+        assert_eq!(Precio::<Centavos, Clp>::default().moneda(), Moneda::CLP);
+
+        assert!(Cotizado::<Centavos, Ars>::new(Cotizacion::Euro, (Centavos(100), None)).is_ok());
+        assert_eq!(
+            Cotizado::<Centavos, Eur>::new(Cotizacion::Euro, (Centavos(100), None)),
+            Err(ConversionError::UnsupportedMoneda(Moneda::ARS))
+        );
+    }
+
+    #[test]
+    fn test_crypto_to_ars() {
+        let crypto =
+            Cotizado::<Centavos, Usd>::new(Cotizacion::Crypto, (Centavos(2950050), None)).unwrap();
+        let blue = Cotizado::<Centavos, Ars>::new(
+            Cotizacion::Blue,
+            (Centavos(56610), Some(Centavos(57190))),
+        )
+        .unwrap();
+
+        let convertido = crypto
+            .to_ars(&blue, Side::Venta, Rounding::default())
+            .unwrap();
+        assert_eq!(convertido.origen, Precio::new(Centavos(2950050)));
+        assert_eq!(convertido.cotizacion, Cotizacion::Blue);
+        assert_eq!(convertido.tasa, Precio::new(Centavos(57190)));
+        assert_eq!(convertido.valor.valor(), Centavos(1687133595));
+        assert_eq!(convertido.valor.moneda(), Moneda::ARS);
+
+        let convertido = crypto
+            .to_ars(&blue, Side::Compra, Rounding::default())
+            .unwrap();
+        assert_eq!(convertido.tasa, Precio::new(Centavos(56610)));
+        assert!(convertido.valor < Precio::new(Centavos(1687133595)));
+    }
+}
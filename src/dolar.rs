@@ -1,3 +1,7 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
 pub const DOLAR_HOY_DOMAIN: &str = "dolarhoy.com";
 pub const DOLAR_HOY_ENDPOINT_BASE: &str = "/i/cotizaciones/";
 
@@ -8,6 +12,14 @@ pub const DOLAR_HOY_RESOURCE_CCL: &str = "dolar-contado-con-liquidacion";
 pub const DOLAR_HOY_RESOURCE_CRYPTO: &str = "bitcoin-usd";
 pub const DOLAR_HOY_RESOURCE_SOLIDARIO: &str = "banco-nacion";
 pub const DOLAR_HOY_RESOURCE_TARJETA: &str = "dolar-tarjeta";
+pub const DOLAR_HOY_RESOURCE_MAYORISTA: &str = "dolar-mayorista";
+pub const DOLAR_HOY_RESOURCE_CRIPTO_USDT: &str = "dolar-cripto";
+pub const DOLAR_HOY_RESOURCE_FUTURO: &str = "dolar-futuro";
+pub const DOLAR_HOY_RESOURCE_EURO: &str = "euro";
+pub const DOLAR_HOY_RESOURCE_EURO_BLUE: &str = "euro-blue";
+pub const DOLAR_HOY_RESOURCE_REAL: &str = "real-brasileno";
+pub const DOLAR_HOY_RESOURCE_PESO_URUGUAYO: &str = "peso-uruguayo";
+pub const DOLAR_HOY_RESOURCE_PESO_CHILENO: &str = "peso-chileno";
 
 pub const DOLAR_HOY_ALIAS_BLUE: &[&str] = &[DOLAR_HOY_RESOURCE_BLUE, "blue"];
 pub const DOLAR_HOY_ALIAS_OFICIAL: &[&str] = &[DOLAR_HOY_RESOURCE_OFICIAL, "oficial"];
@@ -17,29 +29,171 @@ pub const DOLAR_HOY_ALIAS_CRYPTO: &[&str] =
     &[DOLAR_HOY_RESOURCE_CRYPTO, "crypto", "cripto", "bitcoin"];
 pub const DOLAR_HOY_ALIAS_SOLIDARIO: &[&str] = &[DOLAR_HOY_RESOURCE_SOLIDARIO, "solidario", "bna"];
 pub const DOLAR_HOY_ALIAS_TARJETA: &[&str] = &[DOLAR_HOY_RESOURCE_TARJETA, "tarjeta"];
+pub const DOLAR_HOY_ALIAS_MAYORISTA: &[&str] =
+    &[DOLAR_HOY_RESOURCE_MAYORISTA, "mayorista", "wholesale"];
+pub const DOLAR_HOY_ALIAS_CRIPTO_USDT: &[&str] = &[DOLAR_HOY_RESOURCE_CRIPTO_USDT, "usdt"];
+pub const DOLAR_HOY_ALIAS_FUTURO: &[&str] = &[DOLAR_HOY_RESOURCE_FUTURO, "futuro"];
+pub const DOLAR_HOY_ALIAS_EURO: &[&str] = &[DOLAR_HOY_RESOURCE_EURO, "eur"];
+pub const DOLAR_HOY_ALIAS_EURO_BLUE: &[&str] = &[DOLAR_HOY_RESOURCE_EURO_BLUE, "euroblue"];
+pub const DOLAR_HOY_ALIAS_REAL: &[&str] = &[DOLAR_HOY_RESOURCE_REAL, "real", "brl"];
+pub const DOLAR_HOY_ALIAS_PESO_URUGUAYO: &[&str] =
+    &[DOLAR_HOY_RESOURCE_PESO_URUGUAYO, "uruguayo", "uyu"];
+pub const DOLAR_HOY_ALIAS_PESO_CHILENO: &[&str] =
+    &[DOLAR_HOY_RESOURCE_PESO_CHILENO, "chileno", "clp"];
 
 /// An enum representing actual currencies
 /// Not all currency types return values in ARS, some of them use USD
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// New currencies may be added, so matches need a wildcard arm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Moneda {
     /// Argentine Peso
     ARS,
     /// US Dollar
     USD,
+    /// Bitcoin
+    BTC,
+    /// Euro
+    EUR,
+    /// Brazilian Real
+    BRL,
+    /// Uruguayan Peso
+    UYU,
+    /// Chilean Peso
+    CLP,
+}
+
+impl Moneda {
+    /// Returns the currency code (ISO 4217, except for BTC which has none)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ARS => "ARS",
+            Self::USD => "USD",
+            Self::BTC => "BTC",
+            Self::EUR => "EUR",
+            Self::BRL => "BRL",
+            Self::UYU => "UYU",
+            Self::CLP => "CLP",
+        }
+    }
+
+    /// Returns the symbol used for prices in Argentina (Ex: US$ for dollars)
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::ARS => "$",
+            Self::USD => "US$",
+            Self::BTC => "₿",
+            Self::EUR => "€",
+            Self::BRL => "R$",
+            Self::UYU => "$U",
+            Self::CLP => "CLP$",
+        }
+    }
+
+    /// Returns the amount of decimals of the smallest unit (Ex: 2 for cents)
+    pub fn minor_units(&self) -> u32 {
+        match self {
+            Self::ARS | Self::USD | Self::EUR | Self::BRL | Self::UYU => 2,
+            Self::CLP => 0,
+            Self::BTC => 8,
+        }
+    }
 }
 
-impl ToString for Moneda {
+impl fmt::Display for Moneda {
     /// Returns the currency long name
-    fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::ARS => String::from("Peso Argentino"),
-            Self::USD => String::from("Dolar Estadounidense"),
+            Self::ARS => f.write_str("Peso Argentino"),
+            Self::USD => f.write_str("Dolar Estadounidense"),
+            Self::BTC => f.write_str("Bitcoin"),
+            Self::EUR => f.write_str("Euro"),
+            Self::BRL => f.write_str("Real Brasileño"),
+            Self::UYU => f.write_str("Peso Uruguayo"),
+            Self::CLP => f.write_str("Peso Chileno"),
+        }
+    }
+}
+
+/// The error returned when a Moneda can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMonedaError(String);
+
+impl fmt::Display for ParseMonedaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown currency: {}", self.0)
+    }
+}
+
+impl Error for ParseMonedaError {}
+
+impl FromStr for Moneda {
+    type Err = ParseMonedaError;
+
+    /// Parses a currency code or name, ignoring case (Ex: "usd" or "Dólar Estadounidense")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ars" | "peso argentino" | "peso" | "pesos" => Ok(Self::ARS),
+            "usd"
+            | "dolar estadounidense"
+            | "dólar estadounidense"
+            | "dolar"
+            | "dólar"
+            | "dolares"
+            | "dólares" => Ok(Self::USD),
+            "btc" | "xbt" | "bitcoin" => Ok(Self::BTC),
+            "eur" | "euro" | "euros" => Ok(Self::EUR),
+            "brl" | "real brasileño" | "real brasileno" | "real" | "reales" => Ok(Self::BRL),
+            "uyu" | "peso uruguayo" => Ok(Self::UYU),
+            "clp" | "peso chileno" => Ok(Self::CLP),
+            _ => Err(ParseMonedaError(s.to_string())),
         }
     }
 }
 
+/// A currency pair: a quote states the price of one `base` unit in `quote` units
+/// Dollar quotes are USD/ARS, while Crypto is BTC/USD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Par {
+    pub base: Moneda,
+    pub quote: Moneda,
+}
+
+impl Par {
+    /// Returns a Par instance
+    pub fn new(base: Moneda, quote: Moneda) -> Self {
+        Par { base, quote }
+    }
+
+    /// Returns the inverse pair (Ex: ARS/USD for USD/ARS)
+    pub fn invert(&self) -> Self {
+        Par::new(self.quote, self.base)
+    }
+}
+
+impl fmt::Display for Par {
+    /// Returns the pair codes (Ex: USD/ARS)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.base.code(), self.quote.code())
+    }
+}
+
+impl FromStr for Par {
+    type Err = ParseMonedaError;
+
+    /// Parses a pair of currencies separated by a slash (Ex: USD/ARS)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (base, quote) = s
+            .split_once('/')
+            .ok_or_else(|| ParseMonedaError(s.to_string()))?;
+        Ok(Par::new(base.parse()?, quote.parse()?))
+    }
+}
+
 /// An enum that represents all currency types supported
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// New currency types may be added, so matches need a wildcard arm
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum Cotizacion {
     /// Dolar Blue
     Blue,
@@ -55,6 +209,25 @@ pub enum Cotizacion {
     Solidario,
     /// Dolar Tarjeta
     Tarjeta,
+    /// Dolar Mayorista (wholesale, between banks)
+    Mayorista,
+    /// Dolar Cripto (USDT, priced in ARS)
+    CriptoUsdt,
+    /// Dolar Futuro
+    Futuro,
+    /// Euro Oficial
+    Euro,
+    /// Euro Blue
+    EuroBlue,
+    /// Real Brasileño
+    Real,
+    /// Peso Uruguayo
+    PesoUruguayo,
+    /// Peso Chileno
+    PesoChileno,
+    /// A resource not known by this crate (Ex: found with discover_cotizaciones)
+    /// Assumed to be a dollar variant priced in ARS
+    Other(String),
 }
 
 impl Cotizacion {
@@ -71,46 +244,95 @@ impl Cotizacion {
                 Self::Crypto => DOLAR_HOY_RESOURCE_CRYPTO,
                 Self::Solidario => DOLAR_HOY_RESOURCE_SOLIDARIO,
                 Self::Tarjeta => DOLAR_HOY_RESOURCE_TARJETA,
+                Self::Mayorista => DOLAR_HOY_RESOURCE_MAYORISTA,
+                Self::CriptoUsdt => DOLAR_HOY_RESOURCE_CRIPTO_USDT,
+                Self::Futuro => DOLAR_HOY_RESOURCE_FUTURO,
+                Self::Euro => DOLAR_HOY_RESOURCE_EURO,
+                Self::EuroBlue => DOLAR_HOY_RESOURCE_EURO_BLUE,
+                Self::Real => DOLAR_HOY_RESOURCE_REAL,
+                Self::PesoUruguayo => DOLAR_HOY_RESOURCE_PESO_URUGUAYO,
+                Self::PesoChileno => DOLAR_HOY_RESOURCE_PESO_CHILENO,
+                Self::Other(resource) => resource,
             }
         )
     }
 
-    /// Returns the currency in which a price is based on
-    pub fn moneda(&self) -> Moneda {
+    /// Returns the Cotizacion for a resource name, or Cotizacion::Other if it isn't known
+    pub fn from_resource(name: &str) -> Self {
+        get_cotizacion_from_resource_name(name).unwrap_or_else(|| Self::Other(name.to_string()))
+    }
+
+    /// Returns the currency pair priced by this currency type
+    pub fn par(&self) -> Par {
         match self {
-            Self::Crypto => Moneda::USD,
-            _ => Moneda::ARS,
+            Self::Crypto => Par::new(Moneda::BTC, Moneda::USD),
+            Self::Euro | Self::EuroBlue => Par::new(Moneda::EUR, Moneda::ARS),
+            Self::Real => Par::new(Moneda::BRL, Moneda::ARS),
+            Self::PesoUruguayo => Par::new(Moneda::UYU, Moneda::ARS),
+            Self::PesoChileno => Par::new(Moneda::CLP, Moneda::ARS),
+            _ => Par::new(Moneda::USD, Moneda::ARS),
         }
     }
+
+    /// Returns the currency in which a price is based on
+    pub fn moneda(&self) -> Moneda {
+        self.par().quote
+    }
 }
 
-impl ToString for Cotizacion {
+impl fmt::Display for Cotizacion {
     /// Returns the name of the currency type
-    fn to_string(&self) -> String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Blue => String::from("Blue"),
-            Self::Oficial => String::from("Oficial"),
-            Self::Bolsa => String::from("Bolsa"),
-            Self::ContadoConLiqui => String::from("Contado Con Liqui"),
-            Self::Crypto => String::from("Crypto"),
-            Self::Solidario => String::from("Solidario"),
-            Self::Tarjeta => String::from("Tarjeta"),
+            Self::Blue => f.write_str("Blue"),
+            Self::Oficial => f.write_str("Oficial"),
+            Self::Bolsa => f.write_str("Bolsa"),
+            Self::ContadoConLiqui => f.write_str("Contado Con Liqui"),
+            Self::Crypto => f.write_str("Crypto"),
+            Self::Solidario => f.write_str("Solidario"),
+            Self::Tarjeta => f.write_str("Tarjeta"),
+            Self::Mayorista => f.write_str("Mayorista"),
+            Self::CriptoUsdt => f.write_str("Cripto USDT"),
+            Self::Futuro => f.write_str("Futuro"),
+            Self::Euro => f.write_str("Euro"),
+            Self::EuroBlue => f.write_str("Euro Blue"),
+            Self::Real => f.write_str("Real"),
+            Self::PesoUruguayo => f.write_str("Peso Uruguayo"),
+            Self::PesoChileno => f.write_str("Peso Chileno"),
+            Self::Other(resource) => f.write_str(resource),
         }
     }
 }
 
+/// Returns whether the given &str can be used as a resource name
+/// Resource names only contain lowercase letters, digits and dashes (Ex: "dolar-mep")
+pub fn is_resource_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+}
+
 /// Returns an Option<Cotizacion> from a &str describing a resource name
 /// Resource names are currency names that are supported in dolathoy.com (Ex: "dolar-mep")
 pub fn get_cotizacion_from_resource_name(name: &str) -> Option<Cotizacion> {
     match name {
-        DOLAR_HOY_RESOURCE_BLUE => return Some(Cotizacion::Blue),
-        DOLAR_HOY_RESOURCE_OFICIAL => return Some(Cotizacion::Oficial),
-        DOLAR_HOY_RESOURCE_BOLSA => return Some(Cotizacion::Bolsa),
-        DOLAR_HOY_RESOURCE_CCL => return Some(Cotizacion::ContadoConLiqui),
-        DOLAR_HOY_RESOURCE_CRYPTO => return Some(Cotizacion::Crypto),
-        DOLAR_HOY_RESOURCE_SOLIDARIO => return Some(Cotizacion::Solidario),
-        DOLAR_HOY_RESOURCE_TARJETA => return Some(Cotizacion::Tarjeta),
-        _ => return None,
+        DOLAR_HOY_RESOURCE_BLUE => Some(Cotizacion::Blue),
+        DOLAR_HOY_RESOURCE_OFICIAL => Some(Cotizacion::Oficial),
+        DOLAR_HOY_RESOURCE_BOLSA => Some(Cotizacion::Bolsa),
+        DOLAR_HOY_RESOURCE_CCL => Some(Cotizacion::ContadoConLiqui),
+        DOLAR_HOY_RESOURCE_CRYPTO => Some(Cotizacion::Crypto),
+        DOLAR_HOY_RESOURCE_SOLIDARIO => Some(Cotizacion::Solidario),
+        DOLAR_HOY_RESOURCE_TARJETA => Some(Cotizacion::Tarjeta),
+        DOLAR_HOY_RESOURCE_MAYORISTA => Some(Cotizacion::Mayorista),
+        DOLAR_HOY_RESOURCE_CRIPTO_USDT => Some(Cotizacion::CriptoUsdt),
+        DOLAR_HOY_RESOURCE_FUTURO => Some(Cotizacion::Futuro),
+        DOLAR_HOY_RESOURCE_EURO => Some(Cotizacion::Euro),
+        DOLAR_HOY_RESOURCE_EURO_BLUE => Some(Cotizacion::EuroBlue),
+        DOLAR_HOY_RESOURCE_REAL => Some(Cotizacion::Real),
+        DOLAR_HOY_RESOURCE_PESO_URUGUAYO => Some(Cotizacion::PesoUruguayo),
+        DOLAR_HOY_RESOURCE_PESO_CHILENO => Some(Cotizacion::PesoChileno),
+        _ => None,
     }
 }
 
@@ -118,37 +340,122 @@ pub fn get_cotizacion_from_resource_name(name: &str) -> Option<Cotizacion> {
 /// This a more relaxed implementation of get_cotizacion_from_resource_name
 /// Use when trying to obtain a Cotizacion from user input
 pub fn get_cotizacion_from_alias(alias: &str) -> Option<Cotizacion> {
-    if let Some(_) = DOLAR_HOY_ALIAS_BLUE.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_BLUE.contains(&alias) {
         return Some(Cotizacion::Blue);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_OFICIAL.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_OFICIAL.contains(&alias) {
         return Some(Cotizacion::Oficial);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_BOLSA.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_BOLSA.contains(&alias) {
         return Some(Cotizacion::Bolsa);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_CCL.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_CCL.contains(&alias) {
         return Some(Cotizacion::ContadoConLiqui);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_CRYPTO.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_CRYPTO.contains(&alias) {
         return Some(Cotizacion::Crypto);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_SOLIDARIO.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_SOLIDARIO.contains(&alias) {
         return Some(Cotizacion::Solidario);
     }
 
-    if let Some(_) = DOLAR_HOY_ALIAS_TARJETA.into_iter().find(|&s| *s == alias) {
+    if DOLAR_HOY_ALIAS_TARJETA.contains(&alias) {
         return Some(Cotizacion::Tarjeta);
     }
 
+    if DOLAR_HOY_ALIAS_MAYORISTA.contains(&alias) {
+        return Some(Cotizacion::Mayorista);
+    }
+
+    if DOLAR_HOY_ALIAS_CRIPTO_USDT.contains(&alias) {
+        return Some(Cotizacion::CriptoUsdt);
+    }
+
+    if DOLAR_HOY_ALIAS_FUTURO.contains(&alias) {
+        return Some(Cotizacion::Futuro);
+    }
+
+    if DOLAR_HOY_ALIAS_EURO.contains(&alias) {
+        return Some(Cotizacion::Euro);
+    }
+
+    if DOLAR_HOY_ALIAS_EURO_BLUE.contains(&alias) {
+        return Some(Cotizacion::EuroBlue);
+    }
+
+    if DOLAR_HOY_ALIAS_REAL.contains(&alias) {
+        return Some(Cotizacion::Real);
+    }
+
+    if DOLAR_HOY_ALIAS_PESO_URUGUAYO.contains(&alias) {
+        return Some(Cotizacion::PesoUruguayo);
+    }
+
+    if DOLAR_HOY_ALIAS_PESO_CHILENO.contains(&alias) {
+        return Some(Cotizacion::PesoChileno);
+    }
+
     None
 }
 
+/// Keywords found in titles and links of each currency type, checked in order
+/// Other currencies go first, so "Euro Oficial" isn't taken for the oficial dollar nor "Euro Blue" for the blue one
+const DOLAR_HOY_KEYWORDS: &[(&str, Cotizacion)] = &[
+    ("euroblue", Cotizacion::EuroBlue),
+    ("euro", Cotizacion::Euro),
+    ("real", Cotizacion::Real),
+    ("uruguayo", Cotizacion::PesoUruguayo),
+    ("chileno", Cotizacion::PesoChileno),
+    ("contadoconliqui", Cotizacion::ContadoConLiqui),
+    ("ccl", Cotizacion::ContadoConLiqui),
+    ("bolsa", Cotizacion::Bolsa),
+    ("mep", Cotizacion::Bolsa),
+    ("blue", Cotizacion::Blue),
+    ("mayorista", Cotizacion::Mayorista),
+    ("futuro", Cotizacion::Futuro),
+    ("usdt", Cotizacion::CriptoUsdt),
+    ("dolarcripto", Cotizacion::CriptoUsdt),
+    ("bitcoin", Cotizacion::Crypto),
+    ("crypto", Cotizacion::Crypto),
+    ("cripto", Cotizacion::Crypto),
+    ("tarjeta", Cotizacion::Tarjeta),
+    ("turista", Cotizacion::Tarjeta),
+    ("solidario", Cotizacion::Solidario),
+    ("nacion", Cotizacion::Solidario),
+    ("oficial", Cotizacion::Oficial),
+    ("bancos", Cotizacion::Oficial),
+];
+
+/// Returns an Option<Cotizacion> from a &str describing a quote on the site (Ex: "Dólar Blue")
+/// Matches keywords ignoring case, accents and separators, so it also works on links (Ex: "/cotizaciondolarblue")
+/// Note that "Dólar Cripto" is the USDT quote, same as its resource, while "Crypto" and "Bitcoin" are BTC
+pub fn get_cotizacion_from_title(title: &str) -> Option<Cotizacion> {
+    let normalized: String = title
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            'á' => Some('a'),
+            'é' => Some('e'),
+            'í' => Some('i'),
+            'ó' => Some('o'),
+            'ú' => Some('u'),
+            'ñ' => Some('n'),
+            c if c.is_ascii_alphanumeric() => Some(c),
+            _ => None,
+        })
+        .collect();
+
+    DOLAR_HOY_KEYWORDS
+        .iter()
+        .find(|(keyword, _)| normalized.contains(keyword))
+        .map(|(_, cotizacion)| cotizacion.clone())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,6 +469,53 @@ mod tests {
         assert_eq!(Cotizacion::Crypto.moneda(), Moneda::USD);
         assert_eq!(Cotizacion::Solidario.moneda(), Moneda::ARS);
         assert_eq!(Cotizacion::Tarjeta.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::Mayorista.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::CriptoUsdt.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::Futuro.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::Euro.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::EuroBlue.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::Real.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::PesoUruguayo.moneda(), Moneda::ARS);
+        assert_eq!(Cotizacion::PesoChileno.moneda(), Moneda::ARS);
+    }
+
+    #[test]
+    fn test_par_cotizacion() {
+        assert_eq!(Cotizacion::Blue.par().to_string(), "USD/ARS");
+        assert_eq!(Cotizacion::Crypto.par().to_string(), "BTC/USD");
+        assert_eq!(Cotizacion::Mayorista.par().to_string(), "USD/ARS");
+        assert_eq!(Cotizacion::CriptoUsdt.par().to_string(), "USD/ARS");
+        assert_eq!(Cotizacion::Euro.par().to_string(), "EUR/ARS");
+        assert_eq!(Cotizacion::EuroBlue.par().to_string(), "EUR/ARS");
+        assert_eq!(Cotizacion::Real.par().to_string(), "BRL/ARS");
+        assert_eq!(Cotizacion::PesoUruguayo.par().to_string(), "UYU/ARS");
+        assert_eq!(Cotizacion::PesoChileno.par().to_string(), "CLP/ARS");
+        assert_eq!(
+            Cotizacion::Blue.par().invert(),
+            Par::new(Moneda::ARS, Moneda::USD)
+        );
+        assert_eq!("usd/ars".parse(), Ok(Cotizacion::Oficial.par()));
+        assert!("USD-ARS".parse::<Par>().is_err());
+    }
+
+    #[test]
+    fn test_moneda_codes() {
+        assert_eq!(Moneda::ARS.code(), "ARS");
+        assert_eq!(Moneda::USD.symbol(), "US$");
+        assert_eq!(Moneda::BTC.minor_units(), 8);
+        assert_eq!(Moneda::CLP.minor_units(), 0);
+        assert_eq!(Moneda::BRL.symbol(), "R$");
+
+        assert_eq!("ARS".parse(), Ok(Moneda::ARS));
+        assert_eq!("Dólar Estadounidense".parse(), Ok(Moneda::USD));
+        assert_eq!(Moneda::USD.to_string().parse(), Ok(Moneda::USD));
+        assert_eq!(" bitcoin ".parse(), Ok(Moneda::BTC));
+        assert_eq!("Real Brasileño".parse(), Ok(Moneda::BRL));
+        assert_eq!("uyu".parse(), Ok(Moneda::UYU));
+        assert_eq!(
+            "GBP".parse::<Moneda>(),
+            Err(ParseMonedaError("GBP".to_string()))
+        );
     }
 
     #[test]
@@ -195,6 +549,108 @@ mod tests {
             get_cotizacion_from_resource_name("dolar-tarjeta"),
             Some(Cotizacion::Tarjeta)
         );
+        assert_eq!(
+            get_cotizacion_from_resource_name("dolar-mayorista"),
+            Some(Cotizacion::Mayorista)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("dolar-cripto"),
+            Some(Cotizacion::CriptoUsdt)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("dolar-futuro"),
+            Some(Cotizacion::Futuro)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("euro"),
+            Some(Cotizacion::Euro)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("euro-blue"),
+            Some(Cotizacion::EuroBlue)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("real-brasileno"),
+            Some(Cotizacion::Real)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("peso-uruguayo"),
+            Some(Cotizacion::PesoUruguayo)
+        );
+        assert_eq!(
+            get_cotizacion_from_resource_name("peso-chileno"),
+            Some(Cotizacion::PesoChileno)
+        );
+    }
+
+    #[test]
+    fn test_cotizacion_other() {
+        assert_eq!(Cotizacion::from_resource("dolar-blue"), Cotizacion::Blue);
+
+        let other = Cotizacion::from_resource("dolar-qatar");
+        assert_eq!(other, Cotizacion::Other("dolar-qatar".to_string()));
+        assert_eq!(other.endpoint(), "/i/cotizaciones/dolar-qatar");
+        assert_eq!(other.to_string(), "dolar-qatar");
+        assert_eq!(other.par(), Cotizacion::Blue.par());
+
+        assert!(is_resource_name("dolar-qatar"));
+        assert!(!is_resource_name(""));
+        assert!(!is_resource_name("dolar blue"));
+        assert!(!is_resource_name("../dolar"));
+        assert!(!is_resource_name("dolar\r\nHost: evil"));
+    }
+
+    #[test]
+    fn test_get_cotizacion_from_title() {
+        assert_eq!(
+            get_cotizacion_from_title("Dólar blue"),
+            Some(Cotizacion::Blue)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Dólar Oficial promedio"),
+            Some(Cotizacion::Oficial)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Contado con liquidación"),
+            Some(Cotizacion::ContadoConLiqui)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("/cotizaciondolarcontadoconliqui"),
+            Some(Cotizacion::ContadoConLiqui)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Dólar Cripto"),
+            Some(Cotizacion::CriptoUsdt)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Cripto USDT"),
+            Some(Cotizacion::CriptoUsdt)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Bitcoin"),
+            Some(Cotizacion::Crypto)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Crypto"),
+            Some(Cotizacion::Crypto)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Euro Oficial"),
+            Some(Cotizacion::Euro)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Euro Blue"),
+            Some(Cotizacion::EuroBlue)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("/cotizacion-euro-blue"),
+            Some(Cotizacion::EuroBlue)
+        );
+        assert_eq!(
+            get_cotizacion_from_title("Real Brasileño"),
+            Some(Cotizacion::Real)
+        );
+        assert_eq!(get_cotizacion_from_title("Dólar"), None);
     }
 
     #[test]
@@ -218,6 +674,32 @@ mod tests {
             Cotizacion::Tarjeta.endpoint(),
             "/i/cotizaciones/dolar-tarjeta"
         );
+        assert_eq!(
+            Cotizacion::Mayorista.endpoint(),
+            "/i/cotizaciones/dolar-mayorista"
+        );
+        assert_eq!(
+            Cotizacion::CriptoUsdt.endpoint(),
+            "/i/cotizaciones/dolar-cripto"
+        );
+        assert_eq!(
+            Cotizacion::Futuro.endpoint(),
+            "/i/cotizaciones/dolar-futuro"
+        );
+        assert_eq!(Cotizacion::Euro.endpoint(), "/i/cotizaciones/euro");
+        assert_eq!(Cotizacion::EuroBlue.endpoint(), "/i/cotizaciones/euro-blue");
+        assert_eq!(
+            Cotizacion::Real.endpoint(),
+            "/i/cotizaciones/real-brasileno"
+        );
+        assert_eq!(
+            Cotizacion::PesoUruguayo.endpoint(),
+            "/i/cotizaciones/peso-uruguayo"
+        );
+        assert_eq!(
+            Cotizacion::PesoChileno.endpoint(),
+            "/i/cotizaciones/peso-chileno"
+        );
     }
 
     #[test]
@@ -314,4 +796,85 @@ mod tests {
             Some(Cotizacion::Tarjeta)
         );
     }
+
+    #[test]
+    fn test_get_cotizacion_from_alias_mayorista() {
+        assert_eq!(
+            get_cotizacion_from_alias("dolar-mayorista"),
+            Some(Cotizacion::Mayorista)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("mayorista"),
+            Some(Cotizacion::Mayorista)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("wholesale"),
+            Some(Cotizacion::Mayorista)
+        );
+    }
+
+    #[test]
+    fn test_get_cotizacion_from_alias_cripto_usdt() {
+        assert_eq!(
+            get_cotizacion_from_alias("dolar-cripto"),
+            Some(Cotizacion::CriptoUsdt)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("usdt"),
+            Some(Cotizacion::CriptoUsdt)
+        );
+        // "cripto" keeps meaning bitcoin
+        assert_eq!(
+            get_cotizacion_from_alias("cripto"),
+            Some(Cotizacion::Crypto)
+        );
+    }
+
+    #[test]
+    fn test_get_cotizacion_from_alias_futuro() {
+        assert_eq!(
+            get_cotizacion_from_alias("dolar-futuro"),
+            Some(Cotizacion::Futuro)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("futuro"),
+            Some(Cotizacion::Futuro)
+        );
+    }
+
+    #[test]
+    fn test_get_cotizacion_from_alias_otras_monedas() {
+        assert_eq!(get_cotizacion_from_alias("euro"), Some(Cotizacion::Euro));
+        assert_eq!(get_cotizacion_from_alias("eur"), Some(Cotizacion::Euro));
+        assert_eq!(
+            get_cotizacion_from_alias("euro-blue"),
+            Some(Cotizacion::EuroBlue)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("euroblue"),
+            Some(Cotizacion::EuroBlue)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("real-brasileno"),
+            Some(Cotizacion::Real)
+        );
+        assert_eq!(get_cotizacion_from_alias("real"), Some(Cotizacion::Real));
+        assert_eq!(get_cotizacion_from_alias("brl"), Some(Cotizacion::Real));
+        assert_eq!(
+            get_cotizacion_from_alias("peso-uruguayo"),
+            Some(Cotizacion::PesoUruguayo)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("uyu"),
+            Some(Cotizacion::PesoUruguayo)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("peso-chileno"),
+            Some(Cotizacion::PesoChileno)
+        );
+        assert_eq!(
+            get_cotizacion_from_alias("chileno"),
+            Some(Cotizacion::PesoChileno)
+        );
+    }
 }
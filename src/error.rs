@@ -1,4 +1,5 @@
-use std::{error::Error, fmt, io};
+use crate::conversion::ConversionError;
+use std::{error::Error, fmt, io, time::Instant};
 
 /// An enum describing all possibly errors during fetch
 #[derive(thiserror::Error)]
@@ -14,6 +15,78 @@ pub enum ClientError {
 
     #[error("failed to parse data")]
     ParseError(#[from] unhtml::Error),
+
+    #[error("blocked by a bot challenge")]
+    BlockedByChallenge,
+
+    #[error("the site is under maintenance")]
+    MaintenancePage,
+
+    #[error("unexpected content type: {0}")]
+    UnexpectedContentType(String),
+
+    #[error("response is larger than {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    #[error("invalid proxy: {0}")]
+    InvalidProxyError(String),
+
+    #[error("proxy error: {0}")]
+    ProxyError(String),
+
+    #[error("invalid TLS configuration: {0}")]
+    TlsConfigError(String),
+
+    #[error("invalid header: {0}")]
+    InvalidHeaderError(String),
+
+    #[error("rate limit exceeded")]
+    RateLimited,
+
+    #[error("circuit breaker is open")]
+    CircuitOpen { until: Instant },
+
+    #[error("invalid resource name: {0}")]
+    InvalidResourceError(String),
+
+    #[error("conversion failed: {0}")]
+    ConversionError(#[from] ConversionError),
+}
+
+impl ClientError {
+    /// Returns a short name for the error variant, useful as a metrics label
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::RequestError(_) => "request",
+            Self::InvalidResponseError(_) => "invalid_response",
+            Self::ResponseStatusError(_) => "response_status",
+            Self::ParseError(_) => "parse",
+            Self::BlockedByChallenge => "blocked_by_challenge",
+            Self::MaintenancePage => "maintenance_page",
+            Self::UnexpectedContentType(_) => "unexpected_content_type",
+            Self::ResponseTooLarge { .. } => "response_too_large",
+            Self::InvalidProxyError(_) => "invalid_proxy",
+            Self::ProxyError(_) => "proxy",
+            Self::TlsConfigError(_) => "tls_config",
+            Self::InvalidHeaderError(_) => "invalid_header",
+            Self::RateLimited => "rate_limited",
+            Self::CircuitOpen { .. } => "circuit_open",
+            Self::InvalidResourceError(_) => "invalid_resource",
+            Self::ConversionError(_) => "conversion",
+        }
+    }
+}
+
+impl From<crate::client::header::InvalidHeaderName> for ClientError {
+    fn from(e: crate::client::header::InvalidHeaderName) -> Self {
+        ClientError::InvalidHeaderError(e.to_string())
+    }
+}
+
+impl From<crate::client::header::InvalidHeaderValue> for ClientError {
+    fn from(e: crate::client::header::InvalidHeaderValue) -> Self {
+        ClientError::InvalidHeaderError(e.to_string())
+    }
 }
 
 impl fmt::Debug for ClientError {
@@ -0,0 +1,99 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Settings for hedged requests
+/// If a request takes longer than `delay`, a second attempt is started on a new connection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgePolicy {
+    delay: Duration,
+    max_ratio: f64,
+}
+
+impl HedgePolicy {
+    /// Returns a HedgePolicy starting a second attempt after `delay`
+    /// By default, at most 10% of the requests are hedged
+    pub fn new(delay: Duration) -> Self {
+        HedgePolicy {
+            delay,
+            max_ratio: 0.1,
+        }
+    }
+
+    /// Sets the maximum fraction of requests that can be hedged (between 0.0 and 1.0)
+    pub fn max_ratio(mut self, ratio: f64) -> Self {
+        self.max_ratio = ratio.clamp(0.0, 1.0);
+        self
+    }
+}
+
+/// Keeps track of hedged requests so the ratio is never exceeded
+#[derive(Debug)]
+pub(crate) struct Hedger {
+    policy: HedgePolicy,
+    requests: AtomicU64,
+    hedged: AtomicU64,
+}
+
+impl Hedger {
+    pub(crate) fn new(policy: HedgePolicy) -> Self {
+        Hedger {
+            policy,
+            requests: AtomicU64::new(0),
+            hedged: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the delay before starting a second attempt
+    pub(crate) fn delay(&self) -> Duration {
+        self.policy.delay
+    }
+
+    /// Counts a new request
+    pub(crate) fn record_request(&self) {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns true if a second attempt can be started without exceeding the ratio
+    pub(crate) fn try_hedge(&self) -> bool {
+        let requests = self.requests.load(Ordering::Relaxed) as f64;
+        self.hedged
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |hedged| {
+                ((hedged + 1) as f64 <= requests * self.policy.max_ratio).then_some(hedged + 1)
+            })
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hedge_ratio() {
+        let hedger = Hedger::new(HedgePolicy::new(Duration::from_millis(100)).max_ratio(0.25));
+
+        for _ in 0..3 {
+            hedger.record_request();
+        }
+        assert!(!hedger.try_hedge());
+
+        hedger.record_request();
+        assert!(hedger.try_hedge());
+        assert!(!hedger.try_hedge());
+
+        for _ in 0..4 {
+            hedger.record_request();
+        }
+        assert!(hedger.try_hedge());
+        assert!(!hedger.try_hedge());
+    }
+
+    #[test]
+    fn test_hedge_disabled() {
+        let hedger = Hedger::new(HedgePolicy::new(Duration::from_millis(100)).max_ratio(0.0));
+        for _ in 0..100 {
+            hedger.record_request();
+        }
+        assert!(!hedger.try_hedge());
+    }
+}
@@ -0,0 +1,603 @@
+use crate::conversion::{ConversionError, Convertible, Rounding};
+use crate::dolar::Cotizacion;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date, used for the effective dates of a levy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fecha {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Fecha {
+    /// Returns a Fecha instance, or None if the date doesn't exist
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+
+        (1..=days)
+            .contains(&day)
+            .then_some(Fecha { year, month, day })
+    }
+
+    /// Returns the current date in Argentina (UTC-3)
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+
+        Self::from_days((seconds - 3 * 3600).div_euclid(86400))
+    }
+
+    /// Returns the date `days` days after 1970-01-01
+    fn from_days(days: i64) -> Self {
+        // Howard Hinnant's civil_from_days
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z.rem_euclid(146097);
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+
+        Fecha { year, month, day }
+    }
+
+    /// Returns the year
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    /// Returns the month, from 1 to 12
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, from 1 to 31
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+impl fmt::Display for Fecha {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The error returned when a Fecha can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFechaError(String);
+
+impl fmt::Display for ParseFechaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid date: {}", self.0)
+    }
+}
+
+impl Error for ParseFechaError {}
+
+impl FromStr for Fecha {
+    type Err = ParseFechaError;
+
+    /// Parses a date in the YYYY-MM-DD format
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseFechaError(s.to_string());
+
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(error);
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return Err(error());
+        }
+
+        Fecha::new(
+            year.parse().map_err(|_| error())?,
+            month.parse().map_err(|_| error())?,
+            day.parse().map_err(|_| error())?,
+        )
+        .ok_or_else(error)
+    }
+}
+
+/// The kind of purchase a levy applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Destino {
+    /// Buying dollars for savings (dólar ahorro, published as Solidario)
+    Ahorro,
+    /// Paying in foreign currency with a card (dólar tarjeta)
+    Tarjeta,
+}
+
+impl Destino {
+    /// Returns the Destino priced by the given currency type, if any
+    pub fn from_cotizacion(cotizacion: &Cotizacion) -> Option<Self> {
+        match cotizacion {
+            Cotizacion::Solidario => Some(Self::Ahorro),
+            Cotizacion::Tarjeta => Some(Self::Tarjeta),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Destino {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ahorro => f.write_str("ahorro"),
+            Self::Tarjeta => f.write_str("tarjeta"),
+        }
+    }
+}
+
+/// The error returned when a Destino can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDestinoError(String);
+
+impl fmt::Display for ParseDestinoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid destino: {}", self.0)
+    }
+}
+
+impl Error for ParseDestinoError {}
+
+impl FromStr for Destino {
+    type Err = ParseDestinoError;
+
+    /// Parses a purchase kind, ignoring case (Ex: "ahorro" or "Tarjeta")
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ahorro" | "solidario" => Ok(Self::Ahorro),
+            "tarjeta" => Ok(Self::Tarjeta),
+            _ => Err(ParseDestinoError(s.trim().to_string())),
+        }
+    }
+}
+
+/// A named levy, charged as a percentage of the oficial price
+/// It applies from `desde` to `hasta`, both inclusive, or indefinitely if there's no `hasta`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impuesto<T> {
+    pub nombre: String,
+    pub porcentaje: T,
+    pub destinos: Vec<Destino>,
+    pub desde: Fecha,
+    pub hasta: Option<Fecha>,
+}
+
+impl<T> Impuesto<T> {
+    /// Returns whether the levy applies to the given purchase on the given date
+    pub fn applies(&self, destino: Destino, fecha: Fecha) -> bool {
+        let vigente = match self.hasta {
+            Some(hasta) => self.desde <= fecha && fecha <= hasta,
+            None => self.desde <= fecha,
+        };
+
+        vigente && self.destinos.contains(&destino)
+    }
+}
+
+/// The result of applying levies to an oficial price
+#[derive(Debug, Clone, PartialEq)]
+pub struct Calculo<T> {
+    /// The oficial price the levies were applied to
+    pub base: T,
+    /// The amount charged by each levy, in the order they appear in the table
+    pub impuestos: Vec<(String, T)>,
+    /// The base price plus all levies
+    pub total: T,
+}
+
+/// An error found while reading a levy table
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file couldn't be read
+    Io(io::Error),
+    /// A line couldn't be parsed
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read the levy table: {}", e),
+            Self::Invalid { line, message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(e: io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+/// A table of levies, used to derive the ahorro and tarjeta prices from the oficial one
+/// Each levy is rounded on its own (2 decimals by default), as it appears on a statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct TablaImpuestos<T> {
+    impuestos: Vec<Impuesto<T>>,
+    rounding: Rounding,
+}
+
+impl<T: Convertible + FromStr> TablaImpuestos<T> {
+    /// Returns an empty table
+    pub fn empty() -> Self {
+        TablaImpuestos {
+            impuestos: Vec::new(),
+            rounding: Rounding::default(),
+        }
+    }
+
+    /// Returns the built-in table with the main national levies
+    ///
+    /// - Impuesto PAIS (Ley 27.541), 30%, from 2019-12-23 until it expired on 2024-12-22
+    /// - Percepción de Ganancias (RG 4815/2020), 35%, from 2020-09-16
+    /// - Percepción de Ganancias on tarjeta (RG 5232/2022), 45%, from 2022-07-14
+    /// - Percepción de Ganancias on ahorro (RG 5430/2023), 45%, from 2023-10-10
+    /// - Percepción de Bienes Personales on tarjeta (RG 5430/2023), 25%, from 2023-10-10
+    /// - Percepción de Ganancias (RG 5463/2023), 30%, from 2023-12-13
+    ///   It no longer applies to ahorro since 2025-04-14 (RG 5672/2025)
+    ///
+    /// Levies that depend on the amount spent are not included
+    /// (Ex: the 25% Bienes Personales charged on cards above US$ 300 a month before 2023-10-10)
+    pub fn new() -> Self {
+        Self::parse(DEFAULT_TABLE).expect("the built-in levy table is valid")
+    }
+
+    /// Reads a table from a file, see parse for the format
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Parses a table with one levy per line: `nombre; porcentaje; destinos; desde; hasta`
+    /// Destinos are separated by commas, dates use the YYYY-MM-DD format and `hasta` can be empty
+    /// Empty lines and lines starting with # are ignored
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use dolarhoy_core::impuestos::TablaImpuestos;
+    /// use dolarhoy_core::precio::Centavos;
+    ///
+    /// let tabla = TablaImpuestos::<Centavos>::parse("
+    ///     Impuesto PAIS; 30; ahorro, tarjeta; 2019-12-23; 2024-12-22
+    ///     Percepción Ganancias; 30; tarjeta; 2023-12-13;
+    /// ").unwrap();
+    /// ```
+    pub fn parse(config: &str) -> Result<Self, ConfigError> {
+        let mut tabla = Self::empty();
+
+        for (i, line) in config.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = |message: String| ConfigError::Invalid {
+                line: i + 1,
+                message,
+            };
+
+            let fields: Vec<&str> = line.split(';').map(str::trim).collect();
+            let [nombre, porcentaje, destinos, desde, hasta] = fields[..] else {
+                return Err(invalid(format!(
+                    "expected 5 fields, found {}",
+                    fields.len()
+                )));
+            };
+
+            if nombre.is_empty() {
+                return Err(invalid("missing name".to_string()));
+            }
+
+            let porcentaje = porcentaje
+                .parse::<T>()
+                .map_err(|_| invalid(format!("invalid percentage: {}", porcentaje)))?;
+
+            let destinos = destinos
+                .split(',')
+                .map(Destino::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| invalid(e.to_string()))?;
+
+            let desde = desde.parse::<Fecha>().map_err(|e| invalid(e.to_string()))?;
+            let hasta = match hasta {
+                "" => None,
+                hasta => Some(hasta.parse::<Fecha>().map_err(|e| invalid(e.to_string()))?),
+            };
+
+            if hasta.is_some_and(|hasta| hasta < desde) {
+                return Err(invalid("hasta is before desde".to_string()));
+            }
+
+            tabla.insert(Impuesto {
+                nombre: nombre.to_string(),
+                porcentaje,
+                destinos,
+                desde,
+                hasta,
+            });
+        }
+
+        Ok(tabla)
+    }
+
+    /// Sets the rounding applied to each levy
+    pub fn rounding(mut self, rounding: Rounding) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Adds a levy to the table
+    pub fn insert(&mut self, impuesto: Impuesto<T>) {
+        self.impuestos.push(impuesto);
+    }
+
+    /// Removes all levies with the given name
+    pub fn remove(&mut self, nombre: &str) {
+        self.impuestos.retain(|impuesto| impuesto.nombre != nombre);
+    }
+
+    /// Returns all levies in the table
+    pub fn impuestos(&self) -> &[Impuesto<T>] {
+        &self.impuestos
+    }
+
+    /// Returns the levies that apply to the given purchase on the given date
+    pub fn vigentes(&self, destino: Destino, fecha: Fecha) -> impl Iterator<Item = &Impuesto<T>> {
+        self.impuestos
+            .iter()
+            .filter(move |impuesto| impuesto.applies(destino, fecha))
+    }
+
+    /// Applies the levies in effect on the given date to an oficial price
+    /// The oficial price is usually the venta price of Cotizacion::Oficial
+    pub fn calcular(
+        &self,
+        oficial: T,
+        destino: Destino,
+        fecha: Fecha,
+    ) -> Result<Calculo<T>, ConversionError> {
+        let cien = T::from_units(100)?;
+        let mut total = oficial;
+        let mut impuestos = Vec::new();
+
+        for impuesto in self.vigentes(destino, fecha) {
+            let monto = oficial
+                .mul_precio(impuesto.porcentaje, self.shifted())?
+                .div_precio(cien, self.rounding)?;
+
+            total = total.add_precio(monto)?;
+            impuestos.push((impuesto.nombre.clone(), monto));
+        }
+
+        Ok(Calculo {
+            base: oficial,
+            impuestos,
+            total,
+        })
+    }
+
+    /// Keeps two more decimals while multiplying, so only the division by 100 rounds
+    fn shifted(&self) -> Rounding {
+        Rounding::new(
+            self.rounding.decimals().saturating_add(2),
+            self.rounding.mode(),
+        )
+    }
+}
+
+impl<T: Convertible + FromStr> Default for TablaImpuestos<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const DEFAULT_TABLE: &str = "
+# Ley 27.541
+Impuesto PAIS; 30; ahorro, tarjeta; 2019-12-23; 2024-12-22
+# RG 4815/2020
+Percepción Ganancias; 35; ahorro; 2020-09-16; 2023-10-09
+Percepción Ganancias; 35; tarjeta; 2020-09-16; 2022-07-13
+# RG 5232/2022
+Percepción Ganancias; 45; tarjeta; 2022-07-14; 2023-12-12
+# RG 5430/2023
+Percepción Ganancias; 45; ahorro; 2023-10-10; 2023-12-12
+Percepción Bienes Personales; 25; tarjeta; 2023-10-10; 2023-12-12
+# RG 5463/2023, RG 5672/2025 lifted it for ahorro
+Percepción Ganancias; 30; ahorro; 2023-12-13; 2025-04-13
+Percepción Ganancias; 30; tarjeta; 2023-12-13;
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precio::Centavos;
+
+    fn fecha(s: &str) -> Fecha {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_fecha() {
+        assert_eq!(fecha("2024-02-29"), Fecha::new(2024, 2, 29).unwrap());
+        assert_eq!(fecha("2024-02-29").to_string(), "2024-02-29");
+        assert!("2023-02-29".parse::<Fecha>().is_err());
+        assert!("2023-13-01".parse::<Fecha>().is_err());
+        assert!("2023-1-01".parse::<Fecha>().is_err());
+        assert_eq!(
+            "2023-01".parse::<Fecha>(),
+            Err(ParseFechaError("2023-01".to_string()))
+        );
+
+        assert_eq!(Fecha::from_days(0), fecha("1970-01-01"));
+        assert_eq!(Fecha::from_days(19782), fecha("2024-02-29"));
+        assert!(Fecha::today() > fecha("2024-01-01"));
+    }
+
+    #[test]
+    fn test_default_table() {
+        let tabla = TablaImpuestos::<Centavos>::new();
+        let oficial = Centavos(36650);
+
+        // Before the levies
+        let calculo = tabla
+            .calcular(oficial, Destino::Tarjeta, fecha("2019-12-22"))
+            .unwrap();
+        assert_eq!(calculo.total, oficial);
+        assert!(calculo.impuestos.is_empty());
+
+        // PAIS 30% + Ganancias 35%
+        let calculo = tabla
+            .calcular(oficial, Destino::Ahorro, fecha("2023-06-01"))
+            .unwrap();
+        assert_eq!(
+            calculo.impuestos,
+            vec![
+                ("Impuesto PAIS".to_string(), Centavos(10995)),
+                ("Percepción Ganancias".to_string(), Centavos(12828)),
+            ]
+        );
+        assert_eq!(calculo.total, Centavos(60473));
+
+        // Totals for an oficial of 100.00, on a date inside each window
+        let windows = [
+            ("2020-01-15", Destino::Ahorro, 13000),
+            ("2020-01-15", Destino::Tarjeta, 13000),
+            ("2021-06-01", Destino::Ahorro, 16500),
+            ("2021-06-01", Destino::Tarjeta, 16500),
+            ("2022-08-01", Destino::Ahorro, 16500),
+            ("2022-08-01", Destino::Tarjeta, 17500),
+            ("2023-11-01", Destino::Ahorro, 17500),
+            ("2023-11-01", Destino::Tarjeta, 20000),
+            ("2024-06-01", Destino::Ahorro, 16000),
+            ("2024-06-01", Destino::Tarjeta, 16000),
+            ("2025-01-10", Destino::Ahorro, 13000),
+            ("2025-01-10", Destino::Tarjeta, 13000),
+            ("2025-05-01", Destino::Ahorro, 10000),
+            ("2025-05-01", Destino::Tarjeta, 13000),
+        ];
+        for (dia, destino, total) in windows {
+            let calculo = tabla
+                .calcular(Centavos(10000), destino, fecha(dia))
+                .unwrap();
+            assert_eq!(calculo.total, Centavos(total), "{} {:?}", dia, destino);
+        }
+
+        // PAIS expired, only Ganancias 30%
+        let calculo = tabla
+            .calcular(Centavos(105000), Destino::Tarjeta, fecha("2024-12-23"))
+            .unwrap();
+        assert_eq!(calculo.total, Centavos(136500));
+
+        // Ganancias no longer applies to ahorro, but still does to tarjeta
+        let calculo = tabla
+            .calcular(Centavos(105000), Destino::Ahorro, fecha("2025-04-14"))
+            .unwrap();
+        assert_eq!(calculo.total, Centavos(105000));
+
+        let calculo = tabla
+            .calcular(Centavos(105000), Destino::Tarjeta, fecha("2025-04-14"))
+            .unwrap();
+        assert_eq!(calculo.total, Centavos(136500));
+    }
+
+    #[test]
+    fn test_parse_destino() {
+        assert_eq!(" Tarjeta ".parse(), Ok(Destino::Tarjeta));
+        assert_eq!("solidario".parse(), Ok(Destino::Ahorro));
+        assert_eq!(
+            "blue".parse::<Destino>(),
+            Err(ParseDestinoError("blue".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_table() {
+        let config = "
+            # A custom table
+            Impuesto PAIS; 30; ahorro, tarjeta; 2019-12-23; 2024-12-22
+
+            Percepción Ganancias; 45.5; tarjeta; 2022-07-01;
+        ";
+
+        let tabla = TablaImpuestos::<Centavos>::parse(config).unwrap();
+        assert_eq!(tabla.impuestos().len(), 2);
+        assert_eq!(tabla.impuestos()[1].porcentaje, Centavos(4550));
+        assert_eq!(tabla.impuestos()[1].hasta, None);
+
+        let calculo = tabla
+            .calcular(Centavos(10000), Destino::Tarjeta, fecha("2023-01-01"))
+            .unwrap();
+        assert_eq!(calculo.total, Centavos(17550));
+
+        let calculo = tabla
+            .calcular(Centavos(10000), Destino::Ahorro, fecha("2023-01-01"))
+            .unwrap();
+        assert_eq!(calculo.total, Centavos(13000));
+    }
+
+    #[test]
+    fn test_parse_table_errors() {
+        let error = |config: &str| match TablaImpuestos::<Centavos>::parse(config) {
+            Err(ConfigError::Invalid { line, message }) => (line, message),
+            other => panic!("unexpected result: {:?}", other),
+        };
+
+        assert_eq!(error("\nPAIS; 30; ahorro; 2019-12-23").0, 2);
+        assert_eq!(
+            error("PAIS; treinta; ahorro; 2019-12-23;").1,
+            "invalid percentage: treinta"
+        );
+        assert_eq!(
+            error("PAIS; 30; blue; 2019-12-23;").1,
+            "invalid destino: blue"
+        );
+        assert_eq!(
+            error("PAIS; 30; ahorro; 2019-12-23; 2019-01-01").1,
+            "hasta is before desde"
+        );
+    }
+
+    #[test]
+    fn test_remove_and_rounding() {
+        let mut tabla = TablaImpuestos::<f64>::new()
+            .rounding(Rounding::new(0, crate::conversion::RoundingMode::Down));
+        tabla.remove("Impuesto PAIS");
+
+        let calculo = tabla
+            .calcular(366.5, Destino::Ahorro, fecha("2023-01-01"))
+            .unwrap();
+        assert_eq!(
+            calculo.impuestos,
+            vec![("Percepción Ganancias".to_string(), 128.0)]
+        );
+        assert_eq!(calculo.total, 494.5);
+    }
+}
@@ -1,8 +1,58 @@
+#[cfg(feature = "client")]
+#[macro_use]
+mod trace;
+
+/// Contains the Cotizaciones type for computing metrics across quotes (brecha, spreads, cross rates)
+pub mod analytics;
+/// Contains a synchronous version of the DolarHoyClient class
+#[cfg(feature = "blocking")]
+pub mod blocking;
+/// Contains the circuit breaker settings
+#[cfg(feature = "client")]
+pub mod breaker;
 /// Contains the DolarHoyClient class
+#[cfg(feature = "client")]
 pub mod client;
+#[cfg(feature = "client")]
+mod connect;
+/// Contains the Conversion type for converting amounts with a quote
+pub mod conversion;
+/// Contains the currency-tagged price types (Ex: Crypto prices in USD)
+pub mod divisa;
 /// This module contains all currency types supported
 pub mod dolar;
 /// Contains the ClientError enum
+#[cfg(feature = "client")]
 pub mod error;
+/// Contains the request hedging settings
+#[cfg(feature = "client")]
+pub mod hedge;
+/// Contains the levy table used to derive the ahorro and tarjeta prices
+pub mod impuestos;
+/// Contains the rate limit and concurrency settings
+#[cfg(feature = "client")]
+pub mod limit;
+/// Contains the ClientObserver trait for collecting metrics
+#[cfg(feature = "client")]
+pub mod observer;
 /// Includes all utilities for parsing currency prices from HTML
 pub mod parser;
+/// Contains the exact price types (Centavos and Decimal)
+pub mod precio;
+/// Contains the HTTP and SOCKS5 proxy settings
+#[cfg(feature = "client")]
+pub mod proxy;
+/// Contains the Resolve trait and the built-in resolvers
+#[cfg(feature = "client")]
+pub mod resolver;
+/// Contains the Runtime trait and the supported async runtimes
+#[cfg(feature = "client")]
+pub mod runtime;
+/// Contains the tower::Service implementation
+#[cfg(feature = "tower")]
+pub mod service;
+#[cfg(all(test, feature = "client"))]
+mod testing;
+/// Contains the TLS options (extra roots, native roots and certificate pinning)
+#[cfg(feature = "client")]
+pub mod tls;
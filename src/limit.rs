@@ -0,0 +1,257 @@
+use crate::error::ClientError;
+use crate::runtime::Runtime;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// A token-bucket rate limit
+/// Allows `requests` every `per`, with bursts of up to `burst` requests
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    requests: u32,
+    per: Duration,
+    burst: u32,
+}
+
+impl RateLimit {
+    /// Returns a RateLimit allowing `requests` every `per`
+    /// The burst size defaults to `requests`
+    pub fn new(requests: u32, per: Duration) -> Self {
+        let requests = requests.max(1);
+        RateLimit {
+            requests,
+            per,
+            burst: requests,
+        }
+    }
+
+    /// Returns a RateLimit allowing `requests` per second
+    pub fn per_second(requests: u32) -> Self {
+        RateLimit::new(requests, Duration::from_secs(1))
+    }
+
+    /// Returns a RateLimit allowing `requests` per minute
+    pub fn per_minute(requests: u32) -> Self {
+        RateLimit::new(requests, Duration::from_secs(60))
+    }
+
+    /// Sets the maximum amount of requests that can be made at once
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Returns the time it takes to refill a single token
+    fn interval(&self) -> Duration {
+        self.per / self.requests
+    }
+}
+
+/// Limits applied to all requests made to a given host
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HostLimits {
+    /// Rate limit for requests to the host
+    pub rate: Option<RateLimit>,
+    /// Maximum amount of requests in flight at the same time
+    pub max_in_flight: Option<usize>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// A token bucket, callers wait in FIFO order for tokens
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    state: Mutex<Bucket>,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        TokenBucket {
+            limit,
+            state: Mutex::new(Bucket {
+                tokens: limit.burst as f64,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    fn refill(&self, bucket: &mut Bucket) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        let rate = self.limit.interval().as_secs_f64();
+        let refilled = if rate > 0.0 {
+            elapsed / rate
+        } else {
+            f64::INFINITY
+        };
+
+        bucket.tokens = (bucket.tokens + refilled).min(self.limit.burst as f64);
+        bucket.updated = now;
+    }
+
+    /// Waits until a token is available and takes it
+    /// The lock is held while waiting, so callers are served in arrival order
+    async fn acquire(&self, runtime: &dyn Runtime) {
+        let mut bucket = self.state.lock().await;
+        self.refill(&mut bucket);
+
+        if bucket.tokens < 1.0 {
+            let missing = 1.0 - bucket.tokens;
+            runtime.sleep(self.limit.interval().mul_f64(missing)).await;
+            self.refill(&mut bucket);
+        }
+
+        bucket.tokens = (bucket.tokens - 1.0).max(0.0);
+    }
+
+    /// Takes a token if one is available right now and nobody else is waiting
+    fn try_acquire(&self) -> bool {
+        let Ok(mut bucket) = self.state.try_lock() else {
+            return false;
+        };
+
+        self.refill(&mut bucket);
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+/// Enforces the HostLimits of a single host
+pub(crate) struct Limiter {
+    bucket: Option<TokenBucket>,
+    semaphore: Option<Arc<Semaphore>>,
+    runtime: Arc<dyn Runtime>,
+}
+
+impl Limiter {
+    pub(crate) fn new(limits: HostLimits, runtime: Arc<dyn Runtime>) -> Self {
+        Limiter {
+            bucket: limits.rate.map(TokenBucket::new),
+            semaphore: limits
+                .max_in_flight
+                .map(|n| Arc::new(Semaphore::new(n.max(1)))),
+            runtime,
+        }
+    }
+
+    /// Waits for a rate limit token and a concurrency slot
+    /// The returned permit must be held until the request is done
+    pub(crate) async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        if let Some(bucket) = &self.bucket {
+            bucket.acquire(&*self.runtime).await;
+        }
+
+        match &self.semaphore {
+            // The semaphore is never closed
+            Some(semaphore) => semaphore.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Same as acquire, but fails with ClientError::RateLimited instead of waiting
+    pub(crate) fn try_acquire(&self) -> Result<Option<OwnedSemaphorePermit>, ClientError> {
+        let permit = match &self.semaphore {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .try_acquire_owned()
+                    .map_err(|_| ClientError::RateLimited)?,
+            ),
+            None => None,
+        };
+
+        if let Some(bucket) = &self.bucket {
+            if !bucket.try_acquire() {
+                return Err(ClientError::RateLimited);
+            }
+        }
+
+        Ok(permit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::default_runtime;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket_burst() {
+        let limiter = Limiter::new(
+            HostLimits {
+                rate: Some(RateLimit::per_second(2)),
+                max_in_flight: None,
+            },
+            default_runtime(),
+        );
+
+        assert!(limiter.try_acquire().is_ok());
+        assert!(limiter.try_acquire().is_ok());
+        assert!(matches!(
+            limiter.try_acquire(),
+            Err(ClientError::RateLimited)
+        ));
+
+        tokio::time::advance(Duration::from_millis(500)).await;
+        assert!(limiter.try_acquire().is_ok());
+        assert!(limiter.try_acquire().is_err());
+    }
+
+    // Paused time only applies to tokio timers
+    #[cfg(feature = "tokio")]
+    #[tokio::test(start_paused = true)]
+    async fn test_token_bucket_waits() {
+        let limiter = Limiter::new(
+            HostLimits {
+                rate: Some(RateLimit::per_second(4).burst(1)),
+                max_in_flight: None,
+            },
+            default_runtime(),
+        );
+
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_max_in_flight() {
+        let limiter = Limiter::new(
+            HostLimits {
+                rate: None,
+                max_in_flight: Some(1),
+            },
+            default_runtime(),
+        );
+
+        let permit = limiter.acquire().await;
+        assert!(permit.is_some());
+        assert!(matches!(
+            limiter.try_acquire(),
+            Err(ClientError::RateLimited)
+        ));
+
+        drop(permit);
+        assert!(limiter.try_acquire().unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_no_limits() {
+        let limiter = Limiter::new(HostLimits::default(), default_runtime());
+        assert!(limiter.acquire().await.is_none());
+        assert!(limiter.try_acquire().unwrap().is_none());
+    }
+}
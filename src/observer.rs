@@ -0,0 +1,95 @@
+use crate::dolar::Cotizacion;
+use crate::error::ClientError;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The resource homepage requests are reported with, as Cotizacion::Other
+pub const HOMEPAGE: &str = "homepage";
+
+/// A trait for observing the requests made by a DolayHoyClient
+/// All methods do nothing by default, implement the ones you need
+///
+/// Homepage requests (discover_cotizaciones and fetch_homepage_snapshot) are reported
+/// with `Cotizacion::Other(HOMEPAGE)`, so they can be told apart from quote requests
+pub trait ClientObserver: Send + Sync {
+    /// Called before a request is made, including requests that fail fast
+    fn on_request(&self, _cotizacion: &Cotizacion) {}
+
+    /// Called when a request succeeds
+    /// The latency includes the time spent waiting for the rate limiter
+    fn on_success(&self, _cotizacion: &Cotizacion, _latency: Duration, _bytes: usize) {}
+
+    /// Called when a request fails
+    /// Use ClientError::kind to group errors by variant
+    fn on_error(&self, _cotizacion: &Cotizacion, _error: &ClientError, _latency: Duration) {}
+}
+
+/// Allows sharing an observer between the client and the caller
+impl<O: ClientObserver + ?Sized> ClientObserver for Arc<O> {
+    fn on_request(&self, cotizacion: &Cotizacion) {
+        (**self).on_request(cotizacion)
+    }
+
+    fn on_success(&self, cotizacion: &Cotizacion, latency: Duration, bytes: usize) {
+        (**self).on_success(cotizacion, latency, bytes)
+    }
+
+    fn on_error(&self, cotizacion: &Cotizacion, error: &ClientError, latency: Duration) {
+        (**self).on_error(cotizacion, error, latency)
+    }
+}
+
+/// An observer reporting to the `metrics` facade
+///
+/// Exports the following metrics, labeled by `cotizacion`:
+/// - `dolarhoy_requests_total` (counter)
+/// - `dolarhoy_errors_total` (counter, also labeled by error `kind`)
+/// - `dolarhoy_request_duration_seconds` (histogram, also labeled by `outcome`)
+/// - `dolarhoy_response_bytes_total` (counter)
+#[cfg(feature = "metrics")]
+#[derive(Debug, Default, Clone)]
+pub struct MetricsObserver;
+
+#[cfg(feature = "metrics")]
+impl MetricsObserver {
+    /// Returns a new MetricsObserver instance
+    pub fn new() -> Self {
+        MetricsObserver
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl ClientObserver for MetricsObserver {
+    fn on_request(&self, cotizacion: &Cotizacion) {
+        metrics::counter!("dolarhoy_requests_total", "cotizacion" => cotizacion.to_string())
+            .increment(1);
+    }
+
+    fn on_success(&self, cotizacion: &Cotizacion, latency: Duration, bytes: usize) {
+        let label = cotizacion.to_string();
+        metrics::histogram!(
+            "dolarhoy_request_duration_seconds",
+            "cotizacion" => label.clone(),
+            "outcome" => "success"
+        )
+        .record(latency.as_secs_f64());
+        metrics::counter!("dolarhoy_response_bytes_total", "cotizacion" => label)
+            .increment(bytes as u64);
+    }
+
+    fn on_error(&self, cotizacion: &Cotizacion, error: &ClientError, latency: Duration) {
+        let label = cotizacion.to_string();
+        metrics::histogram!(
+            "dolarhoy_request_duration_seconds",
+            "cotizacion" => label.clone(),
+            "outcome" => "error"
+        )
+        .record(latency.as_secs_f64());
+        metrics::counter!(
+            "dolarhoy_errors_total",
+            "cotizacion" => label,
+            "kind" => error.kind()
+        )
+        .increment(1);
+    }
+}
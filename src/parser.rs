@@ -1,6 +1,10 @@
+use crate::dolar::{self, Cotizacion};
 use nom::{
-    bytes::complete::{tag, take_while},
+    bytes::complete::{tag, take_until, take_while, take_while1},
+    character::complete::space0,
     combinator::map_res,
+    multi::many0,
+    sequence::terminated,
     IResult,
 };
 use std::str::FromStr;
@@ -9,6 +13,25 @@ use unhtml::{self, FromHtml};
 pub const HTTP_RESPONSE_STATUS_OK: u32 = 200;
 pub const HTTP_RESPONSE_STATUS_NOT_FOUND: u32 = 404;
 
+/// Markers found in bot-challenge pages (Ex: Cloudflare's "Just a moment...")
+pub const CHALLENGE_MARKERS: &[&str] = &[
+    "cf-browser-verification",
+    "cf_chl_opt",
+    "challenge-platform",
+    "cf-challenge",
+    "<title>just a moment...</title>",
+    "<title>attention required! | cloudflare</title>",
+];
+
+/// Markers found in maintenance and error pages
+pub const MAINTENANCE_MARKERS: &[&str] = &[
+    "en mantenimiento",
+    "sitio en mantenimiento",
+    "under maintenance",
+    "maintenance mode",
+    "<title>service unavailable</title>",
+];
+
 /// A type for pulling the response status of a response
 #[derive(Debug, PartialEq)]
 pub struct HTTPResponse {
@@ -42,7 +65,7 @@ fn is_numeric(c: char) -> bool {
 }
 
 fn from_int(input: &str) -> Result<u32, std::num::ParseIntError> {
-    u32::from_str_radix(input, 10)
+    input.parse::<u32>()
 }
 
 /// A helper function to retrieve a HTTPResponse from a response string
@@ -63,6 +86,267 @@ pub fn http_response(input: &str) -> IResult<&str, HTTPResponse> {
     ))
 }
 
+fn is_token(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn http_header(input: &str) -> IResult<&str, (String, String)> {
+    let (input, name) = take_while1(is_token)(input)?;
+    let (input, _) = tag(":")(input)?;
+    let (input, _) = space0(input)?;
+    let (input, value) = terminated(take_until("\r\n"), tag("\r\n"))(input)?;
+
+    Ok((
+        input,
+        (name.to_ascii_lowercase(), value.trim_end().to_owned()),
+    ))
+}
+
+/// A helper function to retrieve the headers that follow the status line
+/// Header names are returned in lowercase
+pub fn http_headers(input: &str) -> IResult<&str, Vec<(String, String)>> {
+    many0(http_header)(input)
+}
+
+/// Returns the value of the first header with the given name, if any
+pub fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(n, _)| n.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+/// An enum describing what kind of page was returned
+#[derive(Debug, PartialEq, Eq)]
+pub enum PageKind {
+    /// A page with currency prices (or at least not a known error page)
+    Content,
+    /// A bot-challenge page
+    Challenge,
+    /// A maintenance or error page
+    Maintenance,
+}
+
+/// Returns the kind of page found in the given HTML content
+/// Pages containing a .container__data block, quote tiles or links to quotes are always considered content
+pub fn page_kind(content: &str) -> PageKind {
+    if content.contains("container__data")
+        || content.contains("tile is-child")
+        || content.contains(dolar::DOLAR_HOY_ENDPOINT_BASE)
+    {
+        return PageKind::Content;
+    }
+
+    let content = content.to_lowercase();
+    if CHALLENGE_MARKERS.iter().any(|m| content.contains(m)) {
+        return PageKind::Challenge;
+    }
+
+    if MAINTENANCE_MARKERS.iter().any(|m| content.contains(m)) {
+        return PageKind::Maintenance;
+    }
+
+    PageKind::Content
+}
+
+/// Returns true if the Content-Type header describes an HTML document
+pub fn is_html_content_type(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/html"))
+}
+
+/// Returns the position right after the end of the .container__data block
+/// Returns None if the block is missing or hasn't been fully received yet
+pub fn container_end(content: &[u8]) -> Option<usize> {
+    ContainerScanner::new().scan(content)
+}
+
+/// Looks for the end of the .container__data block in content received in chunks
+/// Each call only scans the bytes received since the previous one, plus any incomplete tag
+/// The block is the div with the container__data class, markers in scripts and styles are skipped
+#[derive(Debug, Default, Clone)]
+pub struct ContainerScanner {
+    position: usize,
+    raw_text: Option<&'static [u8]>,
+    depth: Option<usize>,
+}
+
+impl ContainerScanner {
+    /// Returns a new ContainerScanner instance
+    pub fn new() -> Self {
+        ContainerScanner::default()
+    }
+
+    /// Scans the content received so far, which must start with the content given on previous calls
+    /// Returns the position right after the end of the block, once it has been received
+    pub fn scan(&mut self, content: &[u8]) -> Option<usize> {
+        while let Some(offset) = content
+            .get(self.position..)?
+            .iter()
+            .position(|&c| c == b'<')
+        {
+            let start = self.position + offset;
+            let rest = &content[start..];
+
+            // Inside a script or style element, only its closing tag matters
+            if let Some(close) = self.raw_text {
+                if rest.len() < close.len() {
+                    self.position = start;
+                    return None;
+                }
+                if rest[..close.len()].eq_ignore_ascii_case(close) {
+                    self.raw_text = None;
+                }
+                self.position = start + 1;
+                continue;
+            }
+
+            let Some(len) = rest.iter().position(|&c| c == b'>') else {
+                self.position = start;
+                return None;
+            };
+            let tag = &rest[..=len];
+            self.position = start + len + 1;
+
+            let (closing, name) = tag_name(tag);
+            let is_div = name.eq_ignore_ascii_case(b"div");
+            match (closing, self.depth) {
+                (false, _) if name.eq_ignore_ascii_case(b"script") => {
+                    self.raw_text = Some(b"</script");
+                }
+                (false, _) if name.eq_ignore_ascii_case(b"style") => {
+                    self.raw_text = Some(b"</style");
+                }
+                (false, None) if is_div && has_class(tag, "container__data") => {
+                    self.depth = Some(1);
+                }
+                (false, Some(depth)) if is_div => self.depth = Some(depth + 1),
+                (true, Some(1)) if is_div => return Some(self.position),
+                (true, Some(depth)) if is_div => self.depth = Some(depth - 1),
+                _ => {}
+            }
+        }
+
+        self.position = content.len();
+        None
+    }
+}
+
+/// Returns whether the tag is a closing one, along with its name
+fn tag_name(tag: &[u8]) -> (bool, &[u8]) {
+    let tag = &tag[1..];
+    let (closing, tag) = match tag.strip_prefix(b"/") {
+        Some(tag) => (true, tag),
+        None => (false, tag),
+    };
+    let len = tag
+        .iter()
+        .position(|c| !c.is_ascii_alphanumeric())
+        .unwrap_or(tag.len());
+
+    (closing, &tag[..len])
+}
+
+/// Returns true if the class attribute of the tag includes the given class
+fn has_class(tag: &[u8], class: &str) -> bool {
+    std::str::from_utf8(tag)
+        .ok()
+        .and_then(|tag| attribute(tag, "class"))
+        .is_some_and(|classes| classes.split_whitespace().any(|c| c == class))
+}
+
+/// A quote linked from a page, along with the title shown next to it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Widget {
+    pub cotizacion: Cotizacion,
+    pub title: String,
+}
+
+/// Returns every quote linked from the given HTML content (Ex: the homepage), without duplicates
+/// Titles are taken from the title attribute of the tag, or the text of a link
+/// Resources not known by this crate are returned as Cotizacion::Other
+pub fn widgets(content: &str) -> Vec<Widget> {
+    let marker = dolar::DOLAR_HOY_ENDPOINT_BASE;
+    let mut widgets: Vec<Widget> = Vec::new();
+
+    for (position, _) in content.match_indices(marker) {
+        let rest = &content[position + marker.len()..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-'))
+            .unwrap_or(rest.len());
+        let resource = &rest[..end];
+
+        if !dolar::is_resource_name(resource) {
+            continue;
+        }
+
+        let cotizacion = Cotizacion::from_resource(resource);
+        if widgets.iter().any(|w| w.cotizacion == cotizacion) {
+            continue;
+        }
+
+        let title = widget_title(content, position).unwrap_or_else(|| resource.to_string());
+        widgets.push(Widget { cotizacion, title });
+    }
+
+    widgets
+}
+
+/// Returns the title of the tag found around `position`
+fn widget_title(content: &str, position: usize) -> Option<String> {
+    let start = content[..position].rfind('<')?;
+    let end = position + content[position..].find('>')?;
+    let tag = &content[start..end];
+
+    let title = attribute(tag, "title").map(str::to_string).or_else(|| {
+        let is_link = tag.len() > 2
+            && tag[1..].starts_with(['a', 'A'])
+            && tag[2..].starts_with(char::is_whitespace);
+        if !is_link {
+            return None;
+        }
+
+        let inner = &content[end + 1..];
+        let close = inner.find("</a>").or_else(|| inner.find("</A>"))?;
+        Some(text(&inner[..close]))
+    })?;
+
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+/// Returns the value of an attribute in a tag
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    for quote in ['"', '\''] {
+        let prefix = format!(" {}={}", name, quote);
+        if let Some(start) = tag.find(&prefix) {
+            let value = &tag[start + prefix.len()..];
+            return value.find(quote).map(|end| &value[..end]);
+        }
+    }
+
+    None
+}
+
+/// Returns the text of an HTML fragment, without tags
+fn text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    text
+}
+
 /// A type used to parse a currency price
 #[derive(Debug)]
 pub struct PrecioCotizacion<T> {
@@ -79,9 +363,15 @@ impl<T: Send + FromStr> FromHtml for PrecioCotizacion<T> {
             err: String::from("element not found"),
         })?;
 
+        // The homepage shows a currency symbol before the value (Ex: $1185.50)
         let content = el.inner_html();
+        let content = content
+            .trim_start()
+            .trim_start_matches("US$")
+            .trim_start_matches('$')
+            .trim_start();
         let (_, valor): (&str, &str) = take_while::<_, _, nom::error::Error<&str>>(is_float)(
-            content.as_str(),
+            content,
         )
         .map_err(|_: nom::Err<_>| unhtml::Error::TextParseError {
             text: el.inner_html(),
@@ -102,7 +392,7 @@ impl<T: Send + FromStr> FromHtml for PrecioCotizacion<T> {
 }
 
 /// A basic type representing the buy/sell price of a currency
-/// Most types do have buy/sell price, except Crypto
+/// Most types do have buy/sell price, except Crypto, Tarjeta and Futuro
 pub type CompraVenta<T> = (T, Option<T>);
 
 /// A common trait for returning currency prices
@@ -156,9 +446,128 @@ impl<T: Send + Copy + FromStr> PrecioCompraVenta<T> for CotizacionValor<T> {
     }
 }
 
+/// A quote tile as rendered on the homepage
+/// Fields are optional, as not every tile is a quote
+#[derive(Debug, FromHtml)]
+pub struct HomepageTile<T: Send + FromStr> {
+    #[html(selector = "a.title", attr = "inner")]
+    pub title: Option<String>,
+
+    #[html(selector = "a.title", attr = "href")]
+    pub link: Option<String>,
+
+    #[html(selector = ".compra .val")]
+    pub compra: Option<PrecioCotizacion<T>>,
+
+    #[html(selector = ".venta .val")]
+    pub venta: Option<PrecioCotizacion<T>>,
+
+    #[html(selector = ".val")]
+    pub valor: Option<PrecioCotizacion<T>>,
+}
+
+/// A struct for parsing every quote tile of the homepage
+#[derive(Debug, FromHtml)]
+pub struct Homepage<T: Send + FromStr> {
+    #[html(selector = ".tile.is-child")]
+    pub tiles: Vec<HomepageTile<T>>,
+}
+
+/// A quote taken from the homepage
+#[derive(Debug, Clone, PartialEq)]
+pub struct CotizacionHomepage<T> {
+    pub cotizacion: Cotizacion,
+    pub title: String,
+    pub precio: CompraVenta<T>,
+}
+
+impl<T: Send + Copy> PrecioCompraVenta<T> for CotizacionHomepage<T> {
+    fn precio_compra_venta(&self) -> CompraVenta<T> {
+        self.precio
+    }
+
+    fn title(&self) -> String {
+        self.title.clone()
+    }
+}
+
+/// Every quote found on the homepage, in the order they are shown
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot<T> {
+    pub cotizaciones: Vec<CotizacionHomepage<T>>,
+}
+
+impl<T: Send + Copy + FromStr> Snapshot<T> {
+    /// Parses the homepage, mapping each tile to a Cotizacion by its link or title
+    /// Tiles linking to an unknown widget are returned as Cotizacion::Other, other unknown tiles are skipped
+    /// Fails if no quote is found, which usually means the layout changed
+    pub fn from_homepage(content: &str) -> unhtml::Result<Self> {
+        let homepage = Homepage::<T>::from_html(content)?;
+        let mut cotizaciones: Vec<CotizacionHomepage<T>> = Vec::new();
+
+        for tile in homepage.tiles {
+            let title = tile.title.as_deref().map(str::trim).unwrap_or_default();
+            let link = tile.link.as_deref().unwrap_or_default();
+
+            let Some(cotizacion) = tile_cotizacion(title, link) else {
+                continue;
+            };
+            if cotizaciones.iter().any(|c| c.cotizacion == cotizacion) {
+                continue;
+            }
+
+            let precio = match (tile.compra, tile.venta, tile.valor) {
+                (Some(compra), venta, _) => (compra.precio, venta.map(|v| v.precio)),
+                (None, Some(venta), _) => (venta.precio, None),
+                (None, None, Some(valor)) => (valor.precio, None),
+                (None, None, None) => continue,
+            };
+
+            cotizaciones.push(CotizacionHomepage {
+                title: match title {
+                    "" => cotizacion.to_string(),
+                    title => title.split_whitespace().collect::<Vec<_>>().join(" "),
+                },
+                cotizacion,
+                precio,
+            });
+        }
+
+        if cotizaciones.is_empty() {
+            return Err(unhtml::Error::TextParseError {
+                text: String::from("homepage"),
+                type_name: String::from("Snapshot"),
+                err: String::from("no quotes found"),
+            });
+        }
+
+        Ok(Snapshot { cotizaciones })
+    }
+
+    /// Returns the quote for the given currency type, if it was on the homepage
+    pub fn get(&self, cotizacion: &Cotizacion) -> Option<&CotizacionHomepage<T>> {
+        self.cotizaciones
+            .iter()
+            .find(|c| &c.cotizacion == cotizacion)
+    }
+}
+
+/// Returns the Cotizacion shown in a homepage tile
+fn tile_cotizacion(title: &str, link: &str) -> Option<Cotizacion> {
+    if let Some((_, resource)) = link.split_once(dolar::DOLAR_HOY_ENDPOINT_BASE) {
+        let resource = resource.trim_end_matches('/');
+        if dolar::is_resource_name(resource) {
+            return Some(Cotizacion::from_resource(resource));
+        }
+    }
+
+    dolar::get_cotizacion_from_title(title).or_else(|| dolar::get_cotizacion_from_title(link))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::precio::Centavos;
 
     #[test]
     fn parse_response_header() {
@@ -211,6 +620,124 @@ mod tests {
         assert!(!resp.status_not_found());
     }
 
+    #[test]
+    fn parse_response_headers() {
+        let (rest, headers) = http_headers(
+            "Content-Type: text/html; charset=UTF-8\r\nServer:cloudflare\r\nX-Empty: \r\n\r\n",
+        )
+        .unwrap();
+
+        assert_eq!(rest, "\r\n");
+        assert_eq!(
+            headers,
+            vec![
+                (
+                    String::from("content-type"),
+                    String::from("text/html; charset=UTF-8")
+                ),
+                (String::from("server"), String::from("cloudflare")),
+                (String::from("x-empty"), String::new()),
+            ]
+        );
+
+        assert_eq!(
+            header_value(&headers, "Content-Type"),
+            Some("text/html; charset=UTF-8")
+        );
+        assert_eq!(header_value(&headers, "location"), None);
+
+        let (_, headers) = http_headers("").unwrap();
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn test_is_html_content_type() {
+        assert!(is_html_content_type("text/html"));
+        assert!(is_html_content_type("Text/HTML; charset=UTF-8"));
+        assert!(!is_html_content_type("application/json"));
+        assert!(!is_html_content_type("text/plain; charset=UTF-8"));
+    }
+
+    #[test]
+    fn test_page_kind() {
+        let challenge = r#"
+        <!DOCTYPE html>
+        <html><head><title>Just a moment...</title></head>
+        <body><div id="challenge-platform"></div></body></html>
+    "#;
+        assert_eq!(page_kind(challenge), PageKind::Challenge);
+
+        let maintenance = r#"
+        <html><head><title>DolarHoy</title></head>
+        <body><h1>Sitio en mantenimiento</h1></body></html>
+    "#;
+        assert_eq!(page_kind(maintenance), PageKind::Maintenance);
+
+        let content = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Blue</h2>
+        </div>
+    "#;
+        assert_eq!(page_kind(content), PageKind::Content);
+        assert_eq!(page_kind("<html></html>"), PageKind::Content);
+    }
+
+    #[test]
+    fn test_container_end() {
+        let content = r#"<html><body>
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Blue</h2>
+            <div class="data__valores">
+                <p>566.00<span>Compra</span></p>
+                <p>571.00<span>Venta</span></p>
+            </div>
+        </div>
+        <script>/* tracking */</script>
+    </body></html>"#;
+
+        let end = container_end(content.as_bytes()).unwrap();
+        assert!(content[..end].ends_with("</p>\n            </div>\n        </div>"));
+        assert!(content[end..].trim_start().starts_with("<script>"));
+
+        let partial = &content.as_bytes()[..content.find("</span></p>").unwrap()];
+        assert_eq!(container_end(partial), None);
+        assert_eq!(container_end(b"<html></html>"), None);
+    }
+
+    #[test]
+    fn test_container_end_skips_head() {
+        let content = r#"<html><head>
+        <style>.container__data{text-align:center}</style>
+        <script>var block = '<div class="container__data"></div>';</script>
+        <link rel="stylesheet" href="/css/container__data.css">
+    </head><body>
+        <div class="container__data">
+            <div class="data__valores"><p>566.00<span>Compra</span></p></div>
+        </div>
+    </body></html>"#;
+
+        let end = container_end(content.as_bytes()).unwrap();
+        assert!(content[..end].ends_with("</div>\n        </div>"));
+        assert!(content[end..].trim_start().starts_with("</body>"));
+    }
+
+    #[test]
+    fn test_container_scanner_chunks() {
+        let content = r#"<style>.container__data{}</style><DIV class="x container__data"><div><p>566.00</p></div></DIV><p>"#;
+        let expected = container_end(content.as_bytes()).unwrap();
+        assert!(content[..expected].ends_with("</DIV>"));
+
+        // Every split point must give the same result
+        for size in 1..content.len() {
+            let mut scanner = ContainerScanner::new();
+            let end = (size..=content.len())
+                .step_by(size)
+                .chain([content.len()])
+                .find_map(|len| scanner.scan(&content.as_bytes()[..len]));
+            assert_eq!(end, Some(expected), "chunk size {}", size);
+        }
+    }
+
     #[test]
     fn test_cotizacion_compraventa_parse() {
         let content = r#"
@@ -365,4 +892,328 @@ mod tests {
             ". cannot be parsed as f64: conversion error"
         );
     }
+
+    #[test]
+    fn test_cotizacion_compraventa_parse_centavos() {
+        let content = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Blue</h2>
+            <div class="data__valores">
+                <p>566.10<span>Compra</span></p>
+                <p>571.90<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+
+        let cotizacion = CotizacionCompraVenta::<Centavos>::from_html(content).unwrap();
+        assert_eq!(
+            cotizacion.precio_compra_venta(),
+            (Centavos(56610), Some(Centavos(57190)))
+        );
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn test_cotizacion_valor_parse_decimal() {
+        use crate::precio::Decimal;
+
+        let content = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Tarjeta</h2>
+            <div class="data__valores">
+                <p>566.10<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+
+        let cotizacion = CotizacionValor::<Decimal>::from_html(content).unwrap();
+        assert_eq!(cotizacion.valor.precio, Decimal::new(56610, 2));
+        assert_eq!(cotizacion.valor.precio.to_string(), "566.10");
+    }
+
+    #[test]
+    fn test_cotizacion_otras_monedas_parse() {
+        let fixtures = [
+            (
+                r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Euro</h2>
+            <div class="data__valores">
+                <p>1180.50<span>Compra</span></p>
+                <p>1250.25<span>Venta</span></p>
+            </div>
+        </div>
+    "#,
+                "Euro",
+                (Centavos(118050), Some(Centavos(125025))),
+            ),
+            (
+                r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Euro Blue</h2>
+            <div class="data__valores">
+                <p>1430.00<span>Compra</span></p>
+                <p>1460.00<span>Venta</span></p>
+            </div>
+        </div>
+    "#,
+                "Euro Blue",
+                (Centavos(143000), Some(Centavos(146000))),
+            ),
+            (
+                r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Real Brasileño</h2>
+            <div class="data__valores">
+                <p>180.00<span>Compra</span></p>
+                <p>200.00<span>Venta</span></p>
+            </div>
+        </div>
+    "#,
+                "Real Brasileño",
+                (Centavos(18000), Some(Centavos(20000))),
+            ),
+            (
+                r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Peso Uruguayo</h2>
+            <div class="data__valores">
+                <p>23.50<span>Compra</span></p>
+                <p>27.50<span>Venta</span></p>
+            </div>
+        </div>
+    "#,
+                "Peso Uruguayo",
+                (Centavos(2350), Some(Centavos(2750))),
+            ),
+            (
+                r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Peso Chileno</h2>
+            <div class="data__valores">
+                <p>1.05<span>Compra</span></p>
+                <p>1.25<span>Venta</span></p>
+            </div>
+        </div>
+    "#,
+                "Peso Chileno",
+                (Centavos(105), Some(Centavos(125))),
+            ),
+        ];
+
+        for (content, title, precios) in fixtures {
+            let cotizacion = CotizacionCompraVenta::<Centavos>::from_html(content).unwrap();
+            assert_eq!(cotizacion.title(), title);
+            assert_eq!(cotizacion.precio_compra_venta(), precios);
+        }
+    }
+
+    #[test]
+    fn test_cotizacion_dolar_variants_parse() {
+        let mayorista = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Mayorista</h2>
+            <div class="data__valores">
+                <p>1045.50<span>Compra</span></p>
+                <p>1054.50<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+
+        let cotizacion = CotizacionCompraVenta::<Centavos>::from_html(mayorista).unwrap();
+        assert_eq!(cotizacion.title(), "Dólar Mayorista");
+        assert_eq!(
+            cotizacion.precio_compra_venta(),
+            (Centavos(104550), Some(Centavos(105450)))
+        );
+
+        let cripto = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Cripto</h2>
+            <div class="data__valores">
+                <p>1190.00<span>Compra</span></p>
+                <p>1225.10<span>Venta</span></p>
+            </div>
+        </div>
+    "#;
+
+        let cotizacion = CotizacionCompraVenta::<Centavos>::from_html(cripto).unwrap();
+        assert_eq!(cotizacion.title(), "Dólar Cripto");
+        assert_eq!(
+            cotizacion.precio_compra_venta(),
+            (Centavos(119000), Some(Centavos(122510)))
+        );
+
+        let futuro = r#"
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Futuro</h2>
+            <div class="data__valores">
+                <p>1102.00<span>Valor</span></p>
+            </div>
+        </div>
+    "#;
+
+        let cotizacion = CotizacionValor::<Centavos>::from_html(futuro).unwrap();
+        assert_eq!(cotizacion.title(), "Dólar Futuro");
+        assert_eq!(cotizacion.precio_compra_venta(), (Centavos(110200), None));
+    }
+
+    #[test]
+    fn test_widgets() {
+        let content = r#"
+        <html><body>
+            <div class="tile is-child">
+                <a href="/i/cotizaciones/dolar-blue" class="title">
+                    Dólar <b>Blue</b>
+                </a>
+            </div>
+            <iframe src="https://dolarhoy.com/i/cotizaciones/dolar-blue" title="Dólar Blue"></iframe>
+            <iframe src="https://dolarhoy.com/i/cotizaciones/dolar-qatar" title="Dólar Qatar"></iframe>
+            <a class="title" href='/i/cotizaciones/dolar-mep'></a>
+            <a href="/i/cotizaciones/">Cotizaciones</a>
+            <script>load("/i/cotizaciones/peso-uruguayo");</script>
+        </body></html>
+    "#;
+
+        assert_eq!(page_kind(content), PageKind::Content);
+        assert_eq!(
+            widgets(content),
+            vec![
+                Widget {
+                    cotizacion: Cotizacion::Blue,
+                    title: "Dólar Blue".to_string()
+                },
+                Widget {
+                    cotizacion: Cotizacion::Other("dolar-qatar".to_string()),
+                    title: "Dólar Qatar".to_string()
+                },
+                Widget {
+                    cotizacion: Cotizacion::Bolsa,
+                    title: "dolar-mep".to_string()
+                },
+                Widget {
+                    cotizacion: Cotizacion::PesoUruguayo,
+                    title: "peso-uruguayo".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_from_homepage() {
+        let content = r#"
+        <html><body>
+            <div class="tile is-parent">
+                <div class="tile is-child">
+                    <a href="/cotizaciondolarblue" class="title">Dólar blue</a>
+                    <div class="values">
+                        <div class="compra"><div class="label">Compra</div><div class="val">$1185.00</div></div>
+                        <div class="venta"><div class="label">Venta</div><div class="val">$1205.50</div></div>
+                    </div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizaciondolaroficial" class="title">Dólar oficial promedio</a>
+                    <div class="values">
+                        <div class="compra"><div class="label">Compra</div><div class="val">$1040.25</div></div>
+                        <div class="venta"><div class="label">Venta</div><div class="val">$1090.25</div></div>
+                    </div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizacion-dolar-tarjeta" class="title">Dólar Tarjeta</a>
+                    <div class="values">
+                        <div class="venta"><div class="label">Venta</div><div class="val">$1417.33</div></div>
+                    </div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/i/cotizaciones/dolar-qatar" class="title">Dólar Qatar</a>
+                    <div class="values"><div class="val">US$ 1500</div></div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizacion-euro-blue" class="title">Euro Blue</a>
+                    <div class="values"><div class="val">$1460.00</div></div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizacion-euro" class="title">Euro Oficial</a>
+                    <div class="values"><div class="val">$1310.75</div></div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/noticias" class="title">Últimas noticias</a>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizacion-dolar-blue" class="title">Dólar blue</a>
+                    <div class="values"><div class="val">$1.00</div></div>
+                </div>
+            </div>
+        </body></html>
+    "#;
+
+        let snapshot = Snapshot::<Centavos>::from_homepage(content).unwrap();
+        assert_eq!(snapshot.cotizaciones.len(), 6);
+
+        let blue = snapshot.get(&Cotizacion::Blue).unwrap();
+        assert_eq!(blue.title(), "Dólar blue");
+        assert_eq!(
+            blue.precio_compra_venta(),
+            (Centavos(118500), Some(Centavos(120550)))
+        );
+
+        assert_eq!(
+            snapshot.get(&Cotizacion::Oficial).unwrap().precio,
+            (Centavos(104025), Some(Centavos(109025)))
+        );
+        assert_eq!(
+            snapshot.get(&Cotizacion::Tarjeta).unwrap().precio,
+            (Centavos(141733), None)
+        );
+        assert_eq!(
+            snapshot
+                .get(&Cotizacion::Other("dolar-qatar".to_string()))
+                .unwrap()
+                .precio,
+            (Centavos(150000), None)
+        );
+        assert!(snapshot.get(&Cotizacion::Bolsa).is_none());
+
+        // The blue euro has its own entry, so it doesn't replace the oficial one
+        let euro = snapshot.get(&Cotizacion::Euro).unwrap();
+        assert_eq!(euro.title(), "Euro Oficial");
+        assert_eq!(euro.precio, (Centavos(131075), None));
+        let euro_blue = snapshot.get(&Cotizacion::EuroBlue).unwrap();
+        assert_eq!(euro_blue.title(), "Euro Blue");
+        assert_eq!(euro_blue.precio, (Centavos(146000), None));
+
+        assert!(Snapshot::<f64>::from_homepage("<html><body></body></html>").is_err());
+    }
+
+    #[test]
+    fn test_snapshot_keeps_crypto_quotes_apart() {
+        let content = r#"
+        <html><body>
+            <div class="tile is-parent">
+                <div class="tile is-child">
+                    <a href="/cotizacion-bitcoin" class="title">Bitcoin</a>
+                    <div class="values"><div class="val">US$ 67250.50</div></div>
+                </div>
+                <div class="tile is-child">
+                    <a href="/cotizacion-dolar-cripto" class="title">Dólar Cripto</a>
+                    <div class="values">
+                        <div class="compra"><div class="label">Compra</div><div class="val">$1210.00</div></div>
+                        <div class="venta"><div class="label">Venta</div><div class="val">$1235.40</div></div>
+                    </div>
+                </div>
+            </div>
+        </body></html>
+    "#;
+
+        let snapshot = Snapshot::<Centavos>::from_homepage(content).unwrap();
+        assert_eq!(snapshot.cotizaciones.len(), 2);
+
+        let crypto = snapshot.get(&Cotizacion::Crypto).unwrap();
+        assert_eq!(crypto.title(), "Bitcoin");
+        assert_eq!(crypto.precio, (Centavos(6725050), None));
+
+        let usdt = snapshot.get(&Cotizacion::CriptoUsdt).unwrap();
+        assert_eq!(usdt.title(), "Dólar Cripto");
+        assert_eq!(usdt.precio, (Centavos(121000), Some(Centavos(123540))));
+    }
 }
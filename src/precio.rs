@@ -0,0 +1,144 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[cfg(feature = "decimal")]
+pub use rust_decimal::Decimal;
+
+/// A fixed-point price, stored as an amount of cents
+/// Parsed straight from the page text, so 566.10 is exactly 56610 centavos
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Centavos(pub i64);
+
+impl Centavos {
+    /// The amount of cents in a unit
+    pub const SCALE: i64 = 100;
+
+    /// Returns a Centavos instance for the given amount of whole units
+    pub fn from_pesos(pesos: i64) -> Option<Self> {
+        pesos.checked_mul(Self::SCALE).map(Centavos)
+    }
+
+    /// Returns the amount of whole units, truncated towards zero
+    pub fn pesos(&self) -> i64 {
+        self.0 / Self::SCALE
+    }
+
+    /// Returns the amount of cents after the whole units
+    pub fn centavos(&self) -> i64 {
+        self.0 % Self::SCALE
+    }
+}
+
+impl fmt::Display for Centavos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let value = self.0.unsigned_abs();
+        write!(
+            f,
+            "{}{}.{:02}",
+            sign,
+            value / Self::SCALE as u64,
+            value % Self::SCALE as u64
+        )
+    }
+}
+
+/// The error returned when a Centavos value can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCentavosError(String);
+
+impl fmt::Display for ParseCentavosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid centavos value: {}", self.0)
+    }
+}
+
+impl Error for ParseCentavosError {}
+
+impl FromStr for Centavos {
+    type Err = ParseCentavosError;
+
+    /// Parses a decimal number with up to two significant decimals (Ex: 566.10)
+    /// Extra decimals must be zeros, values are never rounded
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseCentavosError(s.to_string());
+
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        let (units, decimals) = digits.split_once('.').unwrap_or((digits, ""));
+        if units.is_empty() && decimals.is_empty() {
+            return Err(error());
+        }
+
+        if !units
+            .bytes()
+            .chain(decimals.bytes())
+            .all(|b| b.is_ascii_digit())
+        {
+            return Err(error());
+        }
+
+        let (cents, rest) = decimals.split_at(decimals.len().min(2));
+        if rest.bytes().any(|b| b != b'0') {
+            return Err(error());
+        }
+
+        let units = match units {
+            "" => 0,
+            units => units.parse::<i64>().map_err(|_| error())?,
+        };
+        let cents = match cents.len() {
+            0 => 0,
+            1 => cents.parse::<i64>().map_err(|_| error())? * 10,
+            _ => cents.parse::<i64>().map_err(|_| error())?,
+        };
+
+        let value = units
+            .checked_mul(Self::SCALE)
+            .and_then(|v| v.checked_add(cents))
+            .ok_or_else(error)?;
+
+        Ok(Centavos(if negative { -value } else { value }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_centavos() {
+        assert_eq!("566.10".parse(), Ok(Centavos(56610)));
+        assert_eq!("566.1".parse(), Ok(Centavos(56610)));
+        assert_eq!("566".parse(), Ok(Centavos(56600)));
+        assert_eq!("566.".parse(), Ok(Centavos(56600)));
+        assert_eq!(".05".parse(), Ok(Centavos(5)));
+        assert_eq!("566.1000".parse(), Ok(Centavos(56610)));
+        assert_eq!("-0.05".parse(), Ok(Centavos(-5)));
+
+        assert!("566.105".parse::<Centavos>().is_err());
+        assert!("1.234,56".parse::<Centavos>().is_err());
+        assert!(".".parse::<Centavos>().is_err());
+        assert!("".parse::<Centavos>().is_err());
+        assert!("--1".parse::<Centavos>().is_err());
+        assert!("+1".parse::<Centavos>().is_err());
+        assert!("92233720368547758.08".parse::<Centavos>().is_err());
+    }
+
+    #[test]
+    fn test_display_centavos() {
+        assert_eq!(Centavos(56610).to_string(), "566.10");
+        assert_eq!(Centavos(5).to_string(), "0.05");
+        assert_eq!(Centavos(-5).to_string(), "-0.05");
+        assert_eq!(Centavos(i64::MIN).to_string(), "-92233720368547758.08");
+
+        assert_eq!(Centavos(56610).pesos(), 566);
+        assert_eq!(Centavos(56610).centavos(), 10);
+        assert_eq!(Centavos::from_pesos(566), Some(Centavos(56600)));
+        assert_eq!(Centavos::from_pesos(i64::MAX), None);
+    }
+}
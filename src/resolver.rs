@@ -0,0 +1,203 @@
+use crate::runtime::{self, Runtime};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::oneshot;
+
+/// The future returned by a Resolve implementation
+pub type Resolving = Pin<Box<dyn Future<Output = io::Result<Vec<SocketAddr>>> + Send>>;
+
+/// A trait for resolving a host name into a list of socket addresses
+/// Implement it to replace the system resolver (Ex: pinned IPs, test fixtures)
+pub trait Resolve: Send + Sync {
+    /// Returns all addresses for the given host and port
+    fn resolve(&self, host: &str, port: u16) -> Resolving;
+}
+
+/// The default resolver, backed by the system's getaddrinfo
+/// Lookups run on the blocking thread pool of the runtime, so they don't block it
+#[derive(Clone)]
+pub struct GaiResolver {
+    runtime: Arc<dyn Runtime>,
+}
+
+impl GaiResolver {
+    /// Returns a new GaiResolver instance, running lookups on the default runtime
+    pub fn new() -> Self {
+        GaiResolver::with_runtime(runtime::default_runtime())
+    }
+
+    /// Returns a GaiResolver running lookups on the given runtime
+    pub(crate) fn with_runtime(runtime: Arc<dyn Runtime>) -> Self {
+        GaiResolver { runtime }
+    }
+}
+
+impl Default for GaiResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for GaiResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GaiResolver").finish_non_exhaustive()
+    }
+}
+
+impl Resolve for GaiResolver {
+    fn resolve(&self, host: &str, port: u16) -> Resolving {
+        let host = host.to_owned();
+        let runtime = self.runtime.clone();
+
+        Box::pin(async move {
+            let (tx, rx) = oneshot::channel();
+            runtime
+                .spawn_blocking(Box::new(move || {
+                    let _ = tx.send(lookup(&host, port));
+                }))
+                .await;
+
+            rx.await
+                .map_err(|_| io::Error::other("lookup task panicked"))?
+        })
+    }
+}
+
+/// Looks up the addresses of a host with getaddrinfo, blocking the current thread
+fn lookup(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    let addrs: Vec<_> = (host, port).to_socket_addrs()?.collect();
+    if addrs.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no addresses found for {}", host),
+        ));
+    }
+
+    Ok(addrs)
+}
+
+/// A resolver backed by a static host map
+/// Addresses are returned as registered, the requested port is ignored
+#[derive(Debug, Default, Clone)]
+pub struct StaticResolver {
+    hosts: HashMap<String, Vec<SocketAddr>>,
+}
+
+impl StaticResolver {
+    /// Returns an empty StaticResolver instance
+    pub fn new() -> Self {
+        StaticResolver::default()
+    }
+
+    /// Registers the addresses for the given host
+    pub fn host(mut self, host: &str, addrs: impl IntoIterator<Item = SocketAddr>) -> Self {
+        self.hosts
+            .entry(host.to_ascii_lowercase())
+            .or_default()
+            .extend(addrs);
+        self
+    }
+}
+
+impl Resolve for StaticResolver {
+    fn resolve(&self, host: &str, _port: u16) -> Resolving {
+        let result = match self.hosts.get(&host.to_ascii_lowercase()) {
+            Some(addrs) if !addrs.is_empty() => Ok(addrs.clone()),
+            _ => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("host {} is not registered", host),
+            )),
+        };
+
+        Box::pin(async move { result })
+    }
+}
+
+/// Sorts addresses by alternating families, starting with the family of the first one
+/// This is the order recommended by RFC 8305 (Happy Eyeballs)
+pub(crate) fn interleave(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let prefer_v6 = addrs.first().map(|a| a.is_ipv6()).unwrap_or(false);
+    let (preferred, other): (Vec<_>, Vec<_>) =
+        addrs.into_iter().partition(|a| a.is_ipv6() == prefer_v6);
+
+    let mut result = Vec::with_capacity(preferred.len() + other.len());
+    let mut preferred = preferred.into_iter();
+    let mut other = other.into_iter();
+
+    loop {
+        match (preferred.next(), other.next()) {
+            (None, None) => break,
+            (a, b) => result.extend(a.into_iter().chain(b)),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interleave_addresses() {
+        let addrs = vec![
+            addr("[::1]:443"),
+            addr("[::2]:443"),
+            addr("[::3]:443"),
+            addr("127.0.0.1:443"),
+        ];
+
+        assert_eq!(
+            interleave(addrs),
+            vec![
+                addr("[::1]:443"),
+                addr("127.0.0.1:443"),
+                addr("[::2]:443"),
+                addr("[::3]:443"),
+            ]
+        );
+
+        let addrs = vec![
+            addr("127.0.0.1:443"),
+            addr("[::1]:443"),
+            addr("127.0.0.2:443"),
+        ];
+        assert_eq!(
+            interleave(addrs),
+            vec![
+                addr("127.0.0.1:443"),
+                addr("[::1]:443"),
+                addr("127.0.0.2:443")
+            ]
+        );
+
+        assert_eq!(interleave(vec![]), vec![]);
+    }
+
+    #[tokio::test]
+    async fn test_gai_resolver() {
+        let resolver = GaiResolver::new();
+        let addrs = resolver.resolve("127.0.0.1", 8443).await.unwrap();
+        assert_eq!(addrs, vec![addr("127.0.0.1:8443")]);
+    }
+
+    #[tokio::test]
+    async fn test_static_resolver() {
+        let resolver = StaticResolver::new().host("DolarHoy.com", vec![addr("127.0.0.1:8443")]);
+
+        let addrs = resolver.resolve("dolarhoy.com", 443).await.unwrap();
+        assert_eq!(addrs, vec![addr("127.0.0.1:8443")]);
+
+        let err = resolver.resolve("example.com", 443).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,186 @@
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncWrite};
+
+#[cfg(not(any(feature = "tokio", feature = "smol", feature = "async-std")))]
+compile_error!(
+    "the client needs an async runtime, enable one of the tokio, smol or async-std features"
+);
+
+/// A connected stream, read and written through the tokio IO traits
+pub trait Io: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> Io for T {}
+
+/// The future returned by Runtime::connect
+pub type Connecting = Pin<Box<dyn Future<Output = io::Result<Box<dyn Io>>> + Send>>;
+
+/// The future returned by Runtime::sleep
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The future returned by Runtime::spawn_blocking, resolved once the task is done
+pub type Blocking = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// A task run by Runtime::spawn_blocking
+pub type BlockingTask = Box<dyn FnOnce() + Send>;
+
+/// A trait for the runtime-specific parts of the client: sockets, timers and blocking tasks
+/// Implement it to run the client on a runtime not supported out of the box
+pub trait Runtime: Send + Sync {
+    /// Opens a TCP connection to the given address
+    fn connect(&self, addr: SocketAddr) -> Connecting;
+
+    /// Waits for the given duration
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Runs a blocking task (Ex: a getaddrinfo lookup) on the runtime's blocking thread pool
+    fn spawn_blocking(&self, task: BlockingTask) -> Blocking;
+}
+
+/// Returns the runtime used when none is configured
+/// Tokio is preferred when more than one runtime feature is enabled
+pub(crate) fn default_runtime() -> Arc<dyn Runtime> {
+    #[cfg(feature = "tokio")]
+    return Arc::new(TokioRuntime);
+
+    #[cfg(all(feature = "smol", not(feature = "tokio")))]
+    return Arc::new(SmolRuntime);
+
+    #[cfg(all(feature = "async-std", not(any(feature = "tokio", feature = "smol"))))]
+    return Arc::new(AsyncStdRuntime);
+}
+
+/// Runs the client on tokio
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TokioRuntime;
+
+#[cfg(feature = "tokio")]
+impl Runtime for TokioRuntime {
+    fn connect(&self, addr: SocketAddr) -> Connecting {
+        Box::pin(async move {
+            let stream = tokio::net::TcpStream::connect(addr).await?;
+            Ok(Box::new(stream) as Box<dyn Io>)
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn spawn_blocking(&self, task: BlockingTask) -> Blocking {
+        let handle = tokio::task::spawn_blocking(task);
+        Box::pin(async move {
+            // A panicking task is reported by the caller, through its own channel
+            let _ = handle.await;
+        })
+    }
+}
+
+/// Runs the client on smol
+#[cfg(feature = "smol")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SmolRuntime;
+
+#[cfg(feature = "smol")]
+impl Runtime for SmolRuntime {
+    fn connect(&self, addr: SocketAddr) -> Connecting {
+        use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+        Box::pin(async move {
+            let stream = smol::net::TcpStream::connect(addr).await?;
+            Ok(Box::new(stream.compat()) as Box<dyn Io>)
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async move {
+            smol::Timer::after(duration).await;
+        })
+    }
+
+    fn spawn_blocking(&self, task: BlockingTask) -> Blocking {
+        Box::pin(smol::unblock(task))
+    }
+}
+
+/// Runs the client on async-std
+#[cfg(feature = "async-std")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AsyncStdRuntime;
+
+#[cfg(feature = "async-std")]
+impl Runtime for AsyncStdRuntime {
+    fn connect(&self, addr: SocketAddr) -> Connecting {
+        use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+        Box::pin(async move {
+            let stream = async_std::net::TcpStream::connect(addr).await?;
+            Ok(Box::new(stream.compat()) as Box<dyn Io>)
+        })
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(async_std::task::sleep(duration))
+    }
+
+    fn spawn_blocking(&self, task: BlockingTask) -> Blocking {
+        Box::pin(async_std::task::spawn_blocking(task))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::DolayHoyClient;
+    use crate::dolar::Cotizacion;
+    use crate::limit::RateLimit;
+    use crate::resolver::StaticResolver;
+    use crate::testing::{refused_addr, serve};
+
+    /// Fetches twice through the given runtime
+    /// Covers connecting, the happy eyeballs fallback and the rate limiter's timer
+    async fn fetch_from_local_server<R: Runtime + 'static>(runtime: R) {
+        let (addr, tls_config) = serve(2);
+        let unreachable = refused_addr();
+
+        let client = DolayHoyClient::builder()
+            .runtime(runtime)
+            .resolver(StaticResolver::new().host("dolarhoy.com", vec![unreachable, addr]))
+            .tls_config(tls_config)
+            .rate_limit(RateLimit::per_second(20).burst(1))
+            .build();
+
+        for _ in 0..2 {
+            let precio = client
+                .fetch_cotizacion::<f64>(Cotizacion::Blue)
+                .await
+                .unwrap();
+            assert_eq!(precio.title(), "Dólar Blue");
+            assert_eq!(precio.precio_compra_venta(), (566.00, Some(571.00)));
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_tokio_runtime() {
+        fetch_from_local_server(TokioRuntime).await;
+    }
+
+    // Unlike tokio::test, block_on keeps the future on the stack, which is too small in debug builds
+    #[cfg(feature = "smol")]
+    #[test]
+    fn test_smol_runtime() {
+        smol::block_on(Box::pin(fetch_from_local_server(SmolRuntime)));
+    }
+
+    #[cfg(feature = "async-std")]
+    #[test]
+    fn test_async_std_runtime() {
+        async_std::task::block_on(Box::pin(fetch_from_local_server(AsyncStdRuntime)));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::client::{DolayHoyClient, PrecioType};
+use crate::dolar::Cotizacion;
+use crate::error::ClientError;
+use crate::parser::PrecioCompraVenta;
+use std::fmt;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// The future returned by CotizacionService
+pub type CotizacionFuture<T> =
+    Pin<Box<dyn Future<Output = Result<Box<dyn PrecioCompraVenta<T> + Send>, ClientError>> + Send>>;
+
+/// A tower::Service fetching the prices of a currency
+/// Where T is any PrecioType: f32, f64, precio::Centavos or precio::Decimal (with the decimal feature)
+///
+/// The client is always ready, its own limits (rate limit, circuit breaker) still apply
+///
+/// # Example
+///
+/// ```no_run
+/// use dolarhoy_core::{client, dolar};
+/// use tower::{Service, ServiceExt};
+///
+/// #[tokio::main]
+/// async fn main() {
+///   let mut service = client::DolayHoyClient::new().service::<f32>();
+///   let price = service.ready().await.unwrap().call(dolar::Cotizacion::Blue).await.unwrap();
+///   println!("{:?}", price.precio_compra_venta());
+/// }
+/// ```
+pub struct CotizacionService<T> {
+    client: DolayHoyClient,
+    precio: PhantomData<fn() -> T>,
+}
+
+impl<T> CotizacionService<T> {
+    /// Returns a CotizacionService using the given client
+    pub fn new(client: DolayHoyClient) -> Self {
+        CotizacionService {
+            client,
+            precio: PhantomData,
+        }
+    }
+
+    /// Returns the client used to make the requests
+    pub fn client(&self) -> &DolayHoyClient {
+        &self.client
+    }
+}
+
+impl<T> Clone for CotizacionService<T> {
+    fn clone(&self) -> Self {
+        CotizacionService::new(self.client.clone())
+    }
+}
+
+impl<T> fmt::Debug for CotizacionService<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CotizacionService").finish_non_exhaustive()
+    }
+}
+
+impl<T: PrecioType> Service<Cotizacion> for CotizacionService<T> {
+    type Response = Box<dyn PrecioCompraVenta<T> + Send>;
+    type Error = ClientError;
+    type Future = CotizacionFuture<T>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, cotizacion: Cotizacion) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move { client.fetch_cotizacion_send(cotizacion).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::StaticResolver;
+    use crate::testing::serve;
+    use std::task::Waker;
+    use std::time::Duration;
+    use tower::{BoxError, ServiceBuilder, ServiceExt};
+
+    #[tokio::test]
+    async fn test_service_call() {
+        let (addr, tls_config) = serve(1);
+        let mut service = DolayHoyClient::builder()
+            .resolver(StaticResolver::new().host("dolarhoy.com", vec![addr]))
+            .tls_config(tls_config)
+            .build()
+            .service::<f64>();
+
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(matches!(service.poll_ready(&mut cx), Poll::Ready(Ok(()))));
+
+        let precio = service.call(Cotizacion::Blue).await.unwrap();
+        assert_eq!(precio.title(), "Dólar Blue");
+        assert_eq!(precio.precio_compra_venta(), (566.00, Some(571.00)));
+    }
+
+    #[tokio::test]
+    async fn test_service_with_middleware() {
+        let client = DolayHoyClient::builder()
+            .resolver(StaticResolver::new())
+            .build();
+
+        let service = ServiceBuilder::new()
+            .timeout(Duration::from_secs(5))
+            .service(client.service::<f64>());
+
+        let result: Result<_, BoxError> = service.oneshot(Cotizacion::Blue).await;
+        let err = result.err().unwrap();
+        assert!(matches!(
+            err.downcast_ref::<ClientError>(),
+            Some(ClientError::RequestError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_service_future_is_send() {
+        let service = DolayHoyClient::builder()
+            .resolver(StaticResolver::new())
+            .build()
+            .service::<f32>();
+
+        // Spawning requires both the future and the response to be Send
+        let result = tokio::spawn(service.oneshot(Cotizacion::Oficial))
+            .await
+            .unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::Arc;
+use std::thread;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::{self, ClientConfig, RootCertStore, ServerConfig};
+
+/// The page served by serve, a Dólar Blue quote of 566.00 / 571.00
+pub(crate) const CONTENT: &str = r#"<html><body>
+        <div class="container__data" style="text-align:center;width:100%">
+            <h2 class="data__titulo">Dólar Blue</h2>
+            <div class="data__valores">
+                <p>566.00<span>Compra</span></p>
+                <p>571.00<span>Venta</span></p>
+            </div>
+        </div>
+    </body></html>"#;
+
+/// Grabs a free local port and closes it, so connecting to it is refused
+pub(crate) fn refused_addr() -> SocketAddr {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+}
+
+/// Serves `requests` HTTPS responses for dolarhoy.com from a plain thread
+/// Returns the server address and a client configuration trusting its certificate
+pub(crate) fn serve(requests: usize) -> (SocketAddr, ClientConfig) {
+    let (addr, cert) = serve_certificate(requests);
+
+    let mut roots = RootCertStore::empty();
+    roots.add(cert).unwrap();
+    let client_config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    (addr, client_config)
+}
+
+/// Same as serve, but returns the server's self-signed certificate instead of a client configuration
+pub(crate) fn serve_certificate(requests: usize) -> (SocketAddr, CertificateDer<'static>) {
+    let key = rcgen::generate_simple_self_signed(vec!["dolarhoy.com".to_string()]).unwrap();
+    let cert = CertificateDer::from(key.cert.der().to_vec());
+    let private_key = PrivateKeyDer::try_from(key.key_pair.serialize_der()).unwrap();
+
+    let server_config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(vec![cert.clone()], private_key)
+        .unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server_config = Arc::new(server_config);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let conn = rustls::ServerConnection::new(server_config.clone()).unwrap();
+            let mut tls = rustls::StreamOwned::new(conn, stream.unwrap());
+
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                match tls.read(&mut chunk) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => request.extend_from_slice(&chunk[..n]),
+                }
+            }
+
+            // The client hangs up once the quote block is read, so errors are ignored
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\n\r\n{}",
+                CONTENT.len(),
+                CONTENT
+            );
+            let _ = tls.write_all(response.as_bytes());
+            tls.conn.send_close_notify();
+            let _ = tls.flush();
+        }
+    });
+
+    (addr, cert)
+}
@@ -0,0 +1,323 @@
+use crate::error::ClientError;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tokio_rustls::rustls::client::danger::{
+    HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
+};
+use tokio_rustls::rustls::client::WebPkiServerVerifier;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use tokio_rustls::rustls::{
+    self, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+
+/// High-level TLS settings used to build the client's rustls::ClientConfig
+/// By default only the webpki roots bundled with the crate are trusted
+#[derive(Debug, Clone)]
+pub struct TlsOptions {
+    roots: RootCertStore,
+    pins: Vec<[u8; 32]>,
+}
+
+impl TlsOptions {
+    /// Returns a TlsOptions instance trusting the bundled webpki roots
+    pub fn new() -> Self {
+        TlsOptions {
+            roots: RootCertStore {
+                roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+            },
+            pins: Vec::new(),
+        }
+    }
+
+    /// Returns a TlsOptions instance with no trusted roots
+    /// At least one root must be added before using it
+    pub fn empty() -> Self {
+        TlsOptions {
+            roots: RootCertStore::empty(),
+            pins: Vec::new(),
+        }
+    }
+
+    /// Adds a DER encoded root certificate (Ex: a corporate CA)
+    pub fn add_root_certificate(mut self, der: &[u8]) -> Result<Self, ClientError> {
+        self.roots
+            .add(CertificateDer::from(der.to_vec()))
+            .map_err(|e| ClientError::TlsConfigError(e.to_string()))?;
+        Ok(self)
+    }
+
+    /// Adds all root certificates found in a PEM encoded bundle
+    pub fn add_root_certificates_pem(mut self, pem: &[u8]) -> Result<Self, ClientError> {
+        for cert in CertificateDer::pem_slice_iter(pem) {
+            let cert = cert.map_err(|e| ClientError::TlsConfigError(e.to_string()))?;
+            self.roots
+                .add(cert)
+                .map_err(|e| ClientError::TlsConfigError(e.to_string()))?;
+        }
+        Ok(self)
+    }
+
+    /// Adds the root certificates from the operating system's store
+    /// Certificates that can't be parsed are skipped
+    #[cfg(feature = "native-roots")]
+    pub fn with_native_roots(mut self) -> Result<Self, ClientError> {
+        let result = rustls_native_certs::load_native_certs();
+        if result.certs.is_empty() {
+            if let Some(e) = result.errors.first() {
+                return Err(ClientError::TlsConfigError(e.to_string()));
+            }
+        }
+
+        self.roots.add_parsable_certificates(result.certs);
+        Ok(self)
+    }
+
+    /// Pins the server to a SHA-256 hash of a SubjectPublicKeyInfo in its chain
+    /// The hash is expected in base64, the format used by HPKP (pin-sha256)
+    /// When pins are set, at least one certificate in the chain must match one of them
+    pub fn pin_sha256(mut self, pin: &str) -> Result<Self, ClientError> {
+        let hash: [u8; 32] = STANDARD
+            .decode(pin.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| ClientError::TlsConfigError(format!("invalid pin: {}", pin)))?;
+
+        self.pins.push(hash);
+        Ok(self)
+    }
+
+    /// Returns the rustls::ClientConfig described by these options
+    pub fn client_config(&self) -> Result<ClientConfig, ClientError> {
+        if self.roots.is_empty() {
+            return Err(ClientError::TlsConfigError(
+                "no root certificates".to_string(),
+            ));
+        }
+
+        if self.pins.is_empty() {
+            return Ok(ClientConfig::builder()
+                .with_root_certificates(self.roots.clone())
+                .with_no_client_auth());
+        }
+
+        let inner = WebPkiServerVerifier::builder(Arc::new(self.roots.clone()))
+            .build()
+            .map_err(|e| ClientError::TlsConfigError(e.to_string()))?;
+
+        Ok(ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(PinnedVerifier {
+                inner,
+                pins: self.pins.clone(),
+            }))
+            .with_no_client_auth())
+    }
+}
+
+impl Default for TlsOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A verifier that checks the chain with webpki and then matches the SPKI pins
+#[derive(Debug)]
+struct PinnedVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: Vec<[u8; 32]>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let verified = self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        )?;
+
+        let pinned = std::iter::once(end_entity)
+            .chain(intermediates)
+            .filter_map(|cert| spki_sha256(cert))
+            .any(|hash| self.pins.contains(&hash));
+
+        if !pinned {
+            return Err(rustls::Error::General(
+                "certificate pin mismatch".to_string(),
+            ));
+        }
+
+        Ok(verified)
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Returns the SHA-256 hash of the SubjectPublicKeyInfo of a certificate
+/// The certificate is parsed with webpki, None is returned if it isn't valid DER
+fn spki_sha256(cert: &CertificateDer<'_>) -> Option<[u8; 32]> {
+    let cert = webpki::EndEntityCert::try_from(cert).ok()?;
+    Some(Sha256::digest(cert.subject_public_key_info()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::DolayHoyClient;
+    use crate::dolar::Cotizacion;
+    use crate::resolver::StaticResolver;
+    use crate::testing::serve_certificate;
+    use std::net::SocketAddr;
+
+    fn certificate() -> (rcgen::CertifiedKey, Vec<u8>) {
+        let key = rcgen::generate_simple_self_signed(vec!["dolarhoy.com".to_string()]).unwrap();
+        let der = key.cert.der().to_vec();
+        (key, der)
+    }
+
+    fn pinned_client(addr: SocketAddr, root: &[u8], pin: &str) -> DolayHoyClient {
+        let options = TlsOptions::empty()
+            .add_root_certificate(root)
+            .unwrap()
+            .pin_sha256(pin)
+            .unwrap();
+
+        DolayHoyClient::builder()
+            .resolver(StaticResolver::new().host("dolarhoy.com", vec![addr]))
+            .tls_options(options)
+            .unwrap()
+            .build()
+    }
+
+    #[test]
+    fn test_spki_sha256() {
+        let (key, der) = certificate();
+        let expected: [u8; 32] = Sha256::digest(key.key_pair.public_key_der()).into();
+        assert_eq!(
+            spki_sha256(&CertificateDer::from(der.clone())),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn test_spki_sha256_malformed() {
+        let (_, der) = certificate();
+        let parse = |bytes: &[u8]| spki_sha256(&CertificateDer::from(bytes.to_vec()));
+
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(b"garbage"), None);
+
+        // Every truncation, and trailing data after the certificate
+        for len in 0..der.len() {
+            assert_eq!(parse(&der[..len]), None, "truncated to {}", len);
+        }
+        assert_eq!(parse(&[der.as_slice(), &[0]].concat()), None);
+
+        // Lengths pointing past the end of the input
+        let mut overlong = der.clone();
+        overlong[1] = 0x84;
+        assert_eq!(parse(&overlong), None);
+        assert_eq!(parse(&[0x30, 0x84, 0xff, 0xff, 0xff, 0xff]), None);
+        assert_eq!(parse(&[0x30, 0x89, 1, 2, 3, 4, 5, 6, 7, 8, 9]), None);
+    }
+
+    #[test]
+    fn test_pin_sha256() {
+        let (key, _) = certificate();
+        let pin = STANDARD.encode(Sha256::digest(key.key_pair.public_key_der()));
+
+        let options = TlsOptions::new().pin_sha256(&pin).unwrap();
+        assert_eq!(options.pins.len(), 1);
+        assert!(options.client_config().is_ok());
+
+        assert!(TlsOptions::new().pin_sha256("not base64!").is_err());
+        assert!(TlsOptions::new().pin_sha256("AAAA").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_pinned_handshake() {
+        let (addr, cert) = serve_certificate(1);
+        let pin = STANDARD.encode(spki_sha256(&cert).unwrap());
+
+        let precio = pinned_client(addr, &cert, &pin)
+            .fetch_cotizacion::<f64>(Cotizacion::Blue)
+            .await
+            .unwrap();
+        assert_eq!(precio.title(), "Dólar Blue");
+    }
+
+    #[tokio::test]
+    async fn test_pin_mismatch_fails_handshake() {
+        let (addr, cert) = serve_certificate(1);
+        let pin = STANDARD.encode([0u8; 32]);
+
+        // The chain is trusted, so only the pin can reject it
+        let err = pinned_client(addr, &cert, &pin)
+            .fetch_cotizacion::<f64>(Cotizacion::Blue)
+            .await
+            .err()
+            .unwrap();
+
+        let ClientError::RequestError(e) = err else {
+            panic!("expected a handshake error, got {:?}", err);
+        };
+        let rustls_error = e.get_ref().and_then(|e| e.downcast_ref::<rustls::Error>());
+        assert_eq!(
+            rustls_error,
+            Some(&rustls::Error::General(
+                "certificate pin mismatch".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_add_root_certificates() {
+        let (key, der) = certificate();
+
+        let options = TlsOptions::empty();
+        assert!(options.client_config().is_err());
+
+        let options = TlsOptions::empty().add_root_certificate(&der).unwrap();
+        assert_eq!(options.roots.len(), 1);
+        assert!(options.client_config().is_ok());
+
+        let options = TlsOptions::empty()
+            .add_root_certificates_pem(key.cert.pem().as_bytes())
+            .unwrap();
+        assert_eq!(options.roots.len(), 1);
+
+        assert!(TlsOptions::empty()
+            .add_root_certificate(b"garbage")
+            .is_err());
+    }
+}
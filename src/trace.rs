@@ -0,0 +1,16 @@
+/// Records a value on a field of the current span, when the tracing feature is enabled
+macro_rules! record {
+    ($field:literal, $value:expr) => {{
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record($field, $value);
+        #[cfg(not(feature = "tracing"))]
+        let _ = &$value;
+    }};
+}
+
+/// Records the milliseconds elapsed since `start` on the elapsed_ms field of the current span
+macro_rules! record_elapsed {
+    ($start:expr) => {
+        record!("elapsed_ms", $start.elapsed().as_millis() as u64)
+    };
+}